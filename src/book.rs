@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use crate::record::GameRecord;
+use crate::{Game, Move};
+
+/// How often each move was played in the positions of a collection of games. Positions and their mirror images share
+/// one entry, with the moves stored as seen from the position with the smaller hash.
+#[allow(dead_code)]
+pub(crate) struct OpeningBook {
+    entries: HashMap<u64, HashMap<Move, u32>>,
+}
+
+/// Collects the first `depth` moves of every game in `games` into a book.
+#[allow(dead_code)]
+pub(crate) fn build_book(games: &[GameRecord], depth: u8) -> OpeningBook {
+    let mut entries: HashMap<u64, HashMap<Move, u32>> = HashMap::new();
+
+    for record in games {
+        for (position, played_move) in record.positions().iter().zip(record.moves()).take(usize::from(depth)) {
+            let canonical_move = if position.position_hash() == position.canonical_hash() {
+                *played_move
+            } else {
+                played_move.mirror_files()
+            };
+            *entries
+                .entry(position.canonical_hash())
+                .or_default()
+                .entry(canonical_move)
+                .or_insert(0) += 1;
+        }
+    }
+
+    OpeningBook { entries }
+}
+
+impl OpeningBook {
+    /// The most frequently played move in `game`, if the position is in the book. Ties go to the move with the
+    /// smallest `Move::to_u32` code, so the choice does not depend on the order of the games.
+    #[allow(dead_code)]
+    pub fn best(&self, game: &Game) -> Option<Move> {
+        let (canonical_move, _) = self
+            .entries
+            .get(&game.canonical_hash())?
+            .iter()
+            .max_by_key(|(candidate, count)| (**count, std::cmp::Reverse(candidate.to_u32())))?;
+
+        let booked_move = if game.position_hash() == game.canonical_hash() {
+            *canonical_move
+        } else {
+            canonical_move.mirror_files()
+        };
+
+        game.is_legal(&booked_move).then_some(booked_move)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record_of(choices: &[usize]) -> GameRecord {
+        let mut game = Game::new();
+        let mut record = GameRecord::new(game);
+        for choice in choices {
+            let played_move = game.valid_moves()[*choice];
+            game.make_move(&played_move);
+            record.push(played_move);
+        }
+        record
+    }
+
+    #[test]
+    fn book_returns_most_played_move() {
+        let games = [record_of(&[3, 0, 1]), record_of(&[3, 2]), record_of(&[5])];
+        let book = build_book(&games, 2);
+
+        assert_eq!(book.best(&Game::new()), Some(Game::new().valid_moves()[3]));
+    }
+
+    #[test]
+    fn book_covers_mirrored_positions() {
+        let start = Game::new();
+        let first_move = start.valid_moves()[0];
+        let mut game = start;
+        game.make_move(&first_move);
+        let reply = game.valid_moves()[0];
+
+        let mut record = GameRecord::new(start);
+        record.push(first_move);
+        record.push(reply);
+        let book = build_book(&[record], 2);
+
+        let mut mirrored = start;
+        mirrored.make_move(&first_move.mirror_files());
+        assert_eq!(book.best(&game), Some(reply));
+        assert_eq!(book.best(&mirrored), Some(reply.mirror_files()));
+    }
+
+    #[test]
+    fn positions_beyond_depth_are_not_booked() {
+        let record = record_of(&[0, 0]);
+        let mut second_position = Game::new();
+        second_position.make_move(&record.moves()[0]);
+
+        assert_eq!(build_book(&[record], 1).best(&second_position), None);
+    }
+}
@@ -1,15 +1,26 @@
 #![allow(clippy::trivially_copy_pass_by_ref)]
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::io::{self, BufReader};
+use std::ops::Range;
 
 use navigation::Coordinate;
+use strum::IntoEnumIterator;
 
+use crate::moves::list_moves;
 use crate::navigation::Direction;
-use crate::tiles::TileType;
+use crate::replay::replay;
+use crate::tiles::{Stride, TileType};
 use crate::ubi::run_loop;
 
 pub mod application;
+pub mod book;
+pub mod moves;
 pub mod navigation;
+pub mod picker;
+pub mod record;
+pub mod replay;
+pub mod session;
 pub mod tiles;
 pub mod ubi;
 
@@ -19,6 +30,30 @@ enum Player {
     Brown,
 }
 
+impl Player {
+    pub const fn opponent(&self) -> Self {
+        match self {
+            Self::White => Self::Brown,
+            Self::Brown => Self::White,
+        }
+    }
+
+    /// The rank the player's tiles start on.
+    #[allow(dead_code)]
+    pub const fn home_rank(&self) -> u8 {
+        match self {
+            Self::White => 0,
+            Self::Brown => BOARD_HEIGHT - 1,
+        }
+    }
+
+    /// The rank the player is heading for, which is the opponent's home rank.
+    #[allow(dead_code)]
+    pub const fn goal_rank(&self) -> u8 {
+        self.opponent().home_rank()
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 enum BarragoonAlignment {
     Horizontal,
@@ -48,14 +83,62 @@ impl BarragoonFace {
         }
     }
 
+    /// Returns the face as it appears to `viewer`, i.e. rotated by 180 degrees when seen from Brown's side of the board.
+    #[allow(dead_code)]
+    pub const fn oriented_for(&self, viewer: Player) -> Self {
+        if matches!(viewer, Player::White) {
+            return *self;
+        }
+
+        match *self {
+            Self::OneWay { direction } => Self::OneWay {
+                direction: direction.opposite(),
+            },
+            Self::OneWayTurnLeft { direction } => Self::OneWayTurnLeft {
+                direction: direction.opposite(),
+            },
+            Self::OneWayTurnRight { direction } => Self::OneWayTurnRight {
+                direction: direction.opposite(),
+            },
+            face => face,
+        }
+    }
+
+    /// Returns the face mirrored along the files. Turning faces change their handedness.
+    #[allow(dead_code)]
+    pub const fn mirror_files(&self) -> Self {
+        match *self {
+            Self::OneWay { direction } => Self::OneWay {
+                direction: direction.mirror_files(),
+            },
+            Self::OneWayTurnLeft { direction } => Self::OneWayTurnRight {
+                direction: direction.mirror_files(),
+            },
+            Self::OneWayTurnRight { direction } => Self::OneWayTurnLeft {
+                direction: direction.mirror_files(),
+            },
+            face => face,
+        }
+    }
+
+    /// All `(enter, leave)` direction pairs a tile may use to travel across this face.
+    #[allow(dead_code)]
+    pub fn traversal_table(&self) -> Vec<(Direction, Direction)> {
+        Direction::iter()
+            .flat_map(|enter_dir| Direction::iter().map(move |leave_dir| (enter_dir, leave_dir)))
+            .filter(|(enter_dir, leave_dir)| self.can_be_traversed(*enter_dir, *leave_dir))
+            .collect()
+    }
+
     pub fn can_be_captured_by(&self, tile_type: TileType) -> bool {
         tile_type != TileType::Two || *self != Self::ForceTurn
     }
 
     pub fn can_be_traversed(self, enter_dir: Direction, leave_dir: Direction) -> bool {
-        let is_horizontal = enter_dir == Bd::East && leave_dir == Bd::West || enter_dir == Bd::West && leave_dir == Bd::East;
+        // both directions are directions of travel, so passing straight through keeps the direction
+        let is_horizontal = enter_dir == leave_dir && (enter_dir == Bd::East || enter_dir == Bd::West);
 
-        let is_vertical = enter_dir == Bd::South && leave_dir == Bd::North || enter_dir == Bd::North && leave_dir == Bd::South;
+        let is_vertical = enter_dir == leave_dir && (enter_dir == Bd::North || enter_dir == Bd::South);
 
         let is_left_turn = enter_dir == Bd::North && leave_dir == Bd::West
             || enter_dir == Bd::South && leave_dir == Bd::East
@@ -109,6 +192,38 @@ struct Tile {
 }
 
 impl Tile {
+    /// Packs the tile into three bits: the tile type in the lower two, the player in the upper one.
+    #[allow(dead_code)]
+    pub const fn index(&self) -> u32 {
+        let type_index = match self.tile_type {
+            TileType::Two => 0,
+            TileType::Three => 1,
+            TileType::Four => 2,
+        };
+        let player_index = match self.player {
+            Player::White => 0,
+            Player::Brown => 1,
+        };
+        type_index | player_index << 2
+    }
+
+    /// Inverse of `index`.
+    #[allow(dead_code)]
+    pub const fn from_index(index: u32) -> Option<Self> {
+        let tile_type = match index & 0b11 {
+            0 => TileType::Two,
+            1 => TileType::Three,
+            2 => TileType::Four,
+            _ => return None,
+        };
+        let player = match index >> 2 {
+            0 => Player::White,
+            1 => Player::Brown,
+            _ => return None,
+        };
+        Some(Self { tile_type, player })
+    }
+
     pub const fn as_fen_char(&self) -> char {
         match self.player {
             Player::White => match self.tile_type {
@@ -126,6 +241,52 @@ impl Tile {
 }
 
 impl SquareContent {
+    pub const fn from_fen_char(c: char) -> Option<Self> {
+        match c {
+            'Z' => Some(Self::Tile(Tile {
+                tile_type: TileType::Two,
+                player: Player::White,
+            })),
+            'z' => Some(Self::Tile(Tile {
+                tile_type: TileType::Two,
+                player: Player::Brown,
+            })),
+            'D' => Some(Self::Tile(Tile {
+                tile_type: TileType::Three,
+                player: Player::White,
+            })),
+            'd' => Some(Self::Tile(Tile {
+                tile_type: TileType::Three,
+                player: Player::Brown,
+            })),
+            'V' => Some(Self::Tile(Tile {
+                tile_type: TileType::Four,
+                player: Player::White,
+            })),
+            'v' => Some(Self::Tile(Tile {
+                tile_type: TileType::Four,
+                player: Player::Brown,
+            })),
+            '+' => Some(Self::Barragoon(Bf::ForceTurn)),
+            '|' => Some(Self::Barragoon(Bf::Straight { alignment: Ba::Vertical })),
+            '-' => Some(Self::Barragoon(Bf::Straight { alignment: Ba::Horizontal })),
+            'Y' => Some(Self::Barragoon(Bf::OneWay { direction: Bd::South })),
+            '^' => Some(Self::Barragoon(Bf::OneWay { direction: Bd::North })),
+            '<' => Some(Self::Barragoon(Bf::OneWay { direction: Bd::West })),
+            '>' => Some(Self::Barragoon(Bf::OneWay { direction: Bd::East })),
+            'x' => Some(Self::Barragoon(Bf::Blocking)),
+            'S' => Some(Self::Barragoon(Bf::OneWayTurnLeft { direction: Bd::South })),
+            'N' => Some(Self::Barragoon(Bf::OneWayTurnLeft { direction: Bd::North })),
+            'E' => Some(Self::Barragoon(Bf::OneWayTurnLeft { direction: Bd::East })),
+            'W' => Some(Self::Barragoon(Bf::OneWayTurnLeft { direction: Bd::West })),
+            's' => Some(Self::Barragoon(Bf::OneWayTurnRight { direction: Bd::South })),
+            'n' => Some(Self::Barragoon(Bf::OneWayTurnRight { direction: Bd::North })),
+            'e' => Some(Self::Barragoon(Bf::OneWayTurnRight { direction: Bd::East })),
+            'w' => Some(Self::Barragoon(Bf::OneWayTurnRight { direction: Bd::West })),
+            _ => None,
+        }
+    }
+
     pub const fn as_fen_char(&self) -> char {
         match self {
             Self::Empty => ' ',
@@ -148,6 +309,15 @@ impl SquareContent {
             Self::Barragoon(Bf::OneWayTurnRight { direction: Bd::West }) => 'w',
         }
     }
+
+    /// Like `as_fen_char`, but directional barragoon glyphs are drawn from `viewer`'s perspective.
+    #[allow(dead_code)]
+    pub const fn as_fen_char_oriented(&self, viewer: Player) -> char {
+        match self {
+            Self::Barragoon(face) => Self::Barragoon(face.oriented_for(viewer)).as_fen_char(),
+            _ => self.as_fen_char(),
+        }
+    }
 }
 
 const BOARD_WIDTH: u8 = 7;
@@ -156,13 +326,35 @@ const BOARD_HEIGHT: u8 = 9;
 const BOARD_HEIGHT_SIGNED: i8 = BOARD_HEIGHT as i8;
 const INITIAL_FEN_STRING: &str = "1vd1dv1/2zdz2/7/1x3x1/x1x1x1x/1x3x1/7/2ZDZ2/1VD1DV1";
 const EMPTY_FEN_STRING: &str = "7/7/7/7/7/7/7/7/7";
+const BARRAGOON_SUPPLY: u8 = 32;
 
 type SC = SquareContent;
 
-#[derive(Debug, Copy, Clone)]
+/// The rule toggles in which Barragoon variants differ. `Default` gives the standard rules.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct Rules {
+    /// Quiet moves are only valid if no capture is available.
+    mandatory_capture: bool,
+    barragoon_supply: u8,
+    /// Without barragoons, tiles pass through and stop on barragoon squares as if they were empty.
+    barragoons_enabled: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            mandatory_capture: false,
+            barragoon_supply: BARRAGOON_SUPPLY,
+            barragoons_enabled: true,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 struct Game {
     board: [[SC; BOARD_WIDTH as usize]; BOARD_HEIGHT as usize],
     current_player: Player,
+    rules: Rules,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -170,7 +362,106 @@ enum FenError {
     UnderfullLine { char_index: usize },
     OverfullLine { char_index: usize },
     TooManyLines { char_index: usize },
+    TooFewLines { char_index: usize },
     InvalidChar { char_index: usize },
+    InvalidPlayer { char_index: usize },
+}
+
+impl std::fmt::Display for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnderfullLine { char_index } => write!(f, "Board rank is not filled at index {char_index}"),
+            Self::OverfullLine { char_index } => write!(f, "Board rank is too full at index {char_index}"),
+            Self::TooManyLines { char_index } => write!(f, "Board has too many ranks at index {char_index}"),
+            Self::TooFewLines { char_index } => write!(f, "Board has too few ranks at index {char_index}"),
+            Self::InvalidChar { char_index } => write!(f, "Board contains invalid char at index {char_index}"),
+            Self::InvalidPlayer { char_index } => write!(f, "Invalid side to move at index {char_index}"),
+        }
+    }
+}
+
+/// The letters used for the tiles in FEN strings, ordered Two, Three, Four. Barragoons keep their characters in
+/// every dialect, so the tile letters must not clash with them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct FenDialect {
+    white_tiles: [char; 3],
+    brown_tiles: [char; 3],
+}
+
+impl Default for FenDialect {
+    fn default() -> Self {
+        let letters = |player| Self::TILE_TYPES.map(|tile_type| Tile { tile_type, player }.as_fen_char());
+        Self {
+            white_tiles: letters(Player::White),
+            brown_tiles: letters(Player::Brown),
+        }
+    }
+}
+
+impl FenDialect {
+    const TILE_TYPES: [TileType; 3] = [TileType::Two, TileType::Three, TileType::Four];
+
+    /// A dialect with the given tile letters. Returns `None` if a letter is used for more than one tile, or could be
+    /// mistaken for a barragoon, an empty square count or the rank separator.
+    #[allow(dead_code)]
+    pub fn new(white_tiles: [char; 3], brown_tiles: [char; 3]) -> Option<Self> {
+        let letters = [white_tiles, brown_tiles].concat();
+        for (index, letter) in letters.iter().enumerate() {
+            let is_barragoon = matches!(SC::from_fen_char(*letter), Some(SC::Barragoon(_)));
+            if is_barragoon || letter.is_ascii_digit() || letter.is_whitespace() || *letter == '/' || letters[..index].contains(letter) {
+                return None;
+            }
+        }
+
+        Some(Self { white_tiles, brown_tiles })
+    }
+
+    const fn tile_letters(&self, player: Player) -> &[char; 3] {
+        match player {
+            Player::White => &self.white_tiles,
+            Player::Brown => &self.brown_tiles,
+        }
+    }
+
+    const fn content_char(&self, content: &SquareContent) -> char {
+        match content {
+            SC::Tile(tile) => {
+                let letters = self.tile_letters(tile.player);
+                match tile.tile_type {
+                    TileType::Two => letters[0],
+                    TileType::Three => letters[1],
+                    TileType::Four => letters[2],
+                }
+            }
+            _ => content.as_fen_char(),
+        }
+    }
+
+    fn content_from_char(&self, c: char) -> Option<SquareContent> {
+        for player in [Player::White, Player::Brown] {
+            if let Some(index) = self.tile_letters(player).iter().position(|&letter| letter == c) {
+                let tile_type = Self::TILE_TYPES[index];
+                return Some(SC::Tile(Tile { tile_type, player }));
+            }
+        }
+
+        match SC::from_fen_char(c) {
+            Some(SC::Barragoon(face)) => Some(SC::Barragoon(face)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum PlacementError {
+    OutOfBounds,
+    Occupied,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(dead_code)]
+enum MoveError {
+    Illegal,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -225,6 +516,15 @@ impl Game {
         Self::from_fen(EMPTY_FEN_STRING).expect("Empty position FEN string is corrupted.")
     }
 
+    /// Builds a game from an empty board with the given squares filled in.
+    pub fn from_squares(squares: impl IntoIterator<Item = (Coordinate, SquareContent)>) -> Self {
+        let mut game = Self::empty();
+        for (coordinate, content) in squares {
+            game.set_content(&coordinate, content);
+        }
+        game
+    }
+
     pub const fn squares(&self) -> SquareIterator<'_> {
         SquareIterator {
             owner_game: self,
@@ -233,6 +533,22 @@ impl Game {
         }
     }
 
+    /// The squares of `rank` from the a-file onwards.
+    #[allow(dead_code)]
+    pub fn rank_squares(&self, rank: u8) -> impl Iterator<Item = (Coordinate, &SquareContent)> {
+        self.squares()
+            .filter(move |square| square.coordinate.rank == rank)
+            .map(|square| (square.coordinate, square.content))
+    }
+
+    /// The squares of `file` from the first rank onwards.
+    #[allow(dead_code)]
+    pub fn file_squares(&self, file: u8) -> impl Iterator<Item = (Coordinate, &SquareContent)> {
+        self.squares()
+            .filter(move |square| square.coordinate.file == file)
+            .map(|square| (square.coordinate, square.content))
+    }
+
     pub const fn contains_coordinate(coordinate: &Coordinate) -> bool {
         coordinate.rank < BOARD_HEIGHT && coordinate.file < BOARD_WIDTH
     }
@@ -241,67 +557,210 @@ impl Game {
         &self.board[coordinate.rank as usize][coordinate.file as usize]
     }
 
+    #[allow(dead_code)]
+    pub const fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    #[allow(dead_code)]
+    pub const fn set_rules(&mut self, rules: Rules) {
+        self.rules = rules;
+    }
+
+    #[allow(dead_code)]
+    pub const fn current_player(&self) -> Player {
+        self.current_player
+    }
+
+    #[allow(dead_code)]
+    pub const fn set_current_player(&mut self, player: Player) {
+        self.current_player = player;
+    }
+
+    pub fn is_empty(&self, coordinate: &Coordinate) -> bool {
+        *self.get_content(coordinate) == SC::Empty
+    }
+
+    pub fn is_occupied(&self, coordinate: &Coordinate) -> bool {
+        !self.is_empty(coordinate)
+    }
+
+    pub const fn set_content(&mut self, coordinate: &Coordinate, content: SquareContent) {
+        self.board[coordinate.rank as usize][coordinate.file as usize] = content;
+    }
+
+    /// Puts `content` onto an empty square of the board.
+    ///
+    /// # Errors
+    /// Fails if the coordinate lies outside of the board or the square is already occupied.
+    pub fn place(&mut self, coordinate: &Coordinate, content: SquareContent) -> Result<(), PlacementError> {
+        if !Self::contains_coordinate(coordinate) {
+            return Err(PlacementError::OutOfBounds);
+        }
+        if self.is_occupied(coordinate) {
+            return Err(PlacementError::Occupied);
+        }
+
+        self.set_content(coordinate, content);
+        Ok(())
+    }
+
+    /// Removes whatever is on the square.
+    ///
+    /// # Errors
+    /// Fails if the coordinate lies outside of the board.
+    pub const fn clear(&mut self, coordinate: &Coordinate) -> Result<(), PlacementError> {
+        if !Self::contains_coordinate(coordinate) {
+            return Err(PlacementError::OutOfBounds);
+        }
+
+        self.set_content(coordinate, SC::Empty);
+        Ok(())
+    }
+
+    /// Plays `tile_move` on the board and passes the turn to the opponent. The move is not checked for legality.
+    pub fn make_move(&mut self, tile_move: &Move) {
+        // positions set up by hand may exceed the supply already, only a move adding to the violation is a bug
+        let was_sound = cfg!(debug_assertions) && self.check_invariants().is_ok();
+
+        match *tile_move {
+            Move::Straight { moving_tile, start, stop }
+            | Move::TileCapture {
+                from: (moving_tile, start),
+                to: (_, stop),
+            } => {
+                self.set_content(&start, SC::Empty);
+                self.set_content(&stop, SC::Tile(moving_tile));
+            }
+            Move::BarragoonCapture { start, stop } => {
+                let moving_content = *self.get_content(&start);
+                self.set_content(&start, SC::Empty);
+                self.set_content(&stop, moving_content);
+            }
+        }
+
+        self.current_player = self.current_player.opponent();
+        debug_assert!(!was_sound || self.check_invariants().is_ok());
+    }
+
+    /// Assembles the capture of the barragoon on `to` by the tile on `from`. Returns `None` if `from` does not hold a
+    /// tile of the current player or `to` holds no barragoon. The capture itself is not checked for legality.
+    #[allow(dead_code)]
+    pub fn build_barragoon_capture(&self, from: &Coordinate, to: &Coordinate) -> Option<Move> {
+        if !Self::contains_coordinate(from) || !Self::contains_coordinate(to) {
+            return None;
+        }
+
+        match (self.get_content(from), self.get_content(to)) {
+            (SC::Tile(tile), SC::Barragoon(_)) if tile.player == self.current_player => {
+                Some(Move::BarragoonCapture { start: *from, stop: *to })
+            }
+            _ => None,
+        }
+    }
+
+    /// Plays `tile_move` if it is one of the valid moves of the position.
+    ///
+    /// # Errors
+    /// Returns `MoveError::Illegal` and leaves the game untouched if the move is not valid.
+    #[allow(dead_code)]
+    pub fn try_make_move(&mut self, tile_move: &Move) -> Result<(), MoveError> {
+        if !self.is_legal(tile_move) {
+            return Err(MoveError::Illegal);
+        }
+
+        self.make_move(tile_move);
+        Ok(())
+    }
+
+    /// Plays all `moves` in order. Either all moves are played or, if one of them fails, none are.
+    ///
+    /// # Errors
+    /// Returns the index of the first move that could not be played together with the reason.
+    #[allow(dead_code)]
+    pub fn apply_moves(&mut self, moves: &[Move]) -> Result<(), (usize, MoveError)> {
+        let mut game = *self;
+        for (index, tile_move) in moves.iter().enumerate() {
+            game.try_make_move(tile_move).map_err(|error| (index, error))?;
+        }
+
+        *self = game;
+        Ok(())
+    }
+
+    /// Passes the turn to the opponent without moving a tile, e.g. for null-move pruning and threat analysis.
+    ///
+    /// Returns `None` if the side to move has no moves, as passing would then hide a position without continuation.
+    #[allow(dead_code)]
+    pub fn null_move(&self) -> Option<Self> {
+        if self.valid_moves().is_empty() {
+            return None;
+        }
+
+        let mut game = *self;
+        game.current_player = self.current_player.opponent();
+        Some(game)
+    }
+
+    /// Lists every square whose content differs between `self` and `other` as `(coordinate, old, new)`.
+    #[allow(dead_code)]
+    pub fn diff(&self, other: &Self) -> Vec<(Coordinate, SquareContent, SquareContent)> {
+        self.squares()
+            .zip(other.squares())
+            .filter(|(old, new)| old.content != new.content)
+            .map(|(old, new)| (old.coordinate, *old.content, *new.content))
+            .collect()
+    }
+
     pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        Self::parse_fen(fen, false, &FenDialect::default())
+    }
+
+    /// Like `from_fen`, but reads the tiles with the letters of `dialect`.
+    ///
+    /// # Errors
+    /// Returns a `FenError` if `fen` is malformed or holds a letter `dialect` does not know.
+    #[allow(dead_code)]
+    pub fn from_fen_with(fen: &str, dialect: &FenDialect) -> Result<Self, FenError> {
+        Self::parse_fen(fen, false, dialect)
+    }
+
+    /// Like `from_fen`, but a rank that ends early is filled up with empty squares instead of being rejected.
+    ///
+    /// # Errors
+    /// Returns a `FenError` for all malformed FEN strings except those with underfull ranks.
+    #[allow(dead_code)]
+    pub fn from_fen_lenient(fen: &str) -> Result<Self, FenError> {
+        Self::parse_fen(fen, true, &FenDialect::default())
+    }
+
+    fn parse_fen(fen: &str, pad_ranks: bool, dialect: &FenDialect) -> Result<Self, FenError> {
         let mut board: [[SC; BOARD_WIDTH as usize]; BOARD_HEIGHT as usize] = [[SC::Empty; BOARD_WIDTH as usize]; BOARD_HEIGHT as usize];
 
         let mut row_ptr: i8 = BOARD_HEIGHT_SIGNED - 1;
         let mut col_ptr: u8 = 0;
 
-        for (index, c) in fen.char_indices() {
+        let board_end = fen.find(char::is_whitespace).unwrap_or(fen.len());
+        let (board_part, fields) = fen.split_at(board_end);
+
+        for (index, c) in board_part.char_indices() {
             let obj: FenParseObject = match c {
-                'Z' => Fpo::Square(SC::Tile(Tile {
-                    tile_type: TileType::Two,
-                    player: Player::White,
-                })),
-                'z' => Fpo::Square(SC::Tile(Tile {
-                    tile_type: TileType::Two,
-                    player: Player::Brown,
-                })),
-                'D' => Fpo::Square(SC::Tile(Tile {
-                    tile_type: TileType::Three,
-                    player: Player::White,
-                })),
-                'd' => Fpo::Square(SC::Tile(Tile {
-                    tile_type: TileType::Three,
-                    player: Player::Brown,
-                })),
-                'V' => Fpo::Square(SC::Tile(Tile {
-                    tile_type: TileType::Four,
-                    player: Player::White,
-                })),
-                'v' => Fpo::Square(SC::Tile(Tile {
-                    tile_type: TileType::Four,
-                    player: Player::Brown,
-                })),
-                '+' => Fpo::Square(SC::Barragoon(Bf::ForceTurn)),
-                '|' => Fpo::Square(SC::Barragoon(Bf::Straight { alignment: Ba::Vertical })),
-                '-' => Fpo::Square(SC::Barragoon(Bf::Straight { alignment: Ba::Horizontal })),
-                'Y' => Fpo::Square(SC::Barragoon(Bf::OneWay { direction: Bd::South })),
-                '^' => Fpo::Square(SC::Barragoon(Bf::OneWay { direction: Bd::North })),
-                '<' => Fpo::Square(SC::Barragoon(Bf::OneWay { direction: Bd::West })),
-                '>' => Fpo::Square(SC::Barragoon(Bf::OneWay { direction: Bd::East })),
-                'x' => Fpo::Square(SC::Barragoon(Bf::Blocking)),
-                'S' => Fpo::Square(SC::Barragoon(Bf::OneWayTurnLeft { direction: Bd::South })),
-                'N' => Fpo::Square(SC::Barragoon(Bf::OneWayTurnLeft { direction: Bd::North })),
-                'E' => Fpo::Square(SC::Barragoon(Bf::OneWayTurnLeft { direction: Bd::East })),
-                'W' => Fpo::Square(SC::Barragoon(Bf::OneWayTurnLeft { direction: Bd::West })),
-                's' => Fpo::Square(SC::Barragoon(Bf::OneWayTurnRight { direction: Bd::South })),
-                'n' => Fpo::Square(SC::Barragoon(Bf::OneWayTurnRight { direction: Bd::North })),
-                'e' => Fpo::Square(SC::Barragoon(Bf::OneWayTurnRight { direction: Bd::East })),
-                'w' => Fpo::Square(SC::Barragoon(Bf::OneWayTurnRight { direction: Bd::West })),
                 '1'..='7' => Fpo::JumpCol(
                     c.to_digit(10)
                         .map(|d| u8::try_from(d).expect("Cannot parse digit."))
                         .ok_or(FenError::InvalidChar { char_index: index })?,
                 ),
                 '/' => Fpo::SkipRow,
-                _ => Fpo::InvalidChar,
+                _ => dialect.content_from_char(c).map_or(Fpo::InvalidChar, Fpo::Square),
             };
 
             let row_idx = usize::try_from(row_ptr).expect("Row pointer was negative");
 
             match obj {
                 Fpo::Square(content) => {
+                    if col_ptr == BOARD_WIDTH {
+                        return Result::Err(FenError::OverfullLine { char_index: index });
+                    }
                     board[row_idx][col_ptr as usize] = content;
                     col_ptr += 1;
                 }
@@ -312,7 +771,7 @@ impl Game {
                     }
                 }
                 Fpo::SkipRow => {
-                    if col_ptr == BOARD_WIDTH {
+                    if col_ptr == BOARD_WIDTH || pad_ranks {
                         col_ptr = 0;
                         row_ptr -= 1;
                     } else {
@@ -329,14 +788,48 @@ impl Game {
             }
         }
 
-        // todo: initialize player from fen string
+        if row_ptr > 0 {
+            return Result::Err(FenError::TooFewLines { char_index: board_end });
+        }
+        if col_ptr < BOARD_WIDTH && !pad_ranks {
+            return Result::Err(FenError::UnderfullLine { char_index: board_end });
+        }
+
+        // the side to move is the first field after the board, further fields are not supported yet
+        let current_player = match fields.split_whitespace().next() {
+            None | Some("w") => Player::White,
+            Some("b") => Player::Brown,
+            Some(token) => {
+                let token_offset = fields.find(token).expect("Token was taken from the fields.");
+                return Err(FenError::InvalidPlayer {
+                    char_index: board_end + token_offset,
+                });
+            }
+        };
+
         Ok(Self {
             board,
-            current_player: Player::White,
+            current_player,
+            rules: Rules::default(),
         })
     }
 
+    /// The complete FEN string of the position: the board placement followed by the side to move, `w` or `b`.
     pub fn as_fen(&self) -> String {
+        let side = match self.current_player {
+            Player::White => 'w',
+            Player::Brown => 'b',
+        };
+        format!("{} {side}", self.board_fen())
+    }
+
+    /// The piece placement part of the FEN string.
+    pub fn board_fen(&self) -> String {
+        self.board_fen_with(&FenDialect::default())
+    }
+
+    /// The piece placement part of the FEN string, with the tiles written in the letters of `dialect`.
+    pub fn board_fen_with(&self, dialect: &FenDialect) -> String {
         let mut fen_string = String::new();
 
         for row in self.board.iter().rev() {
@@ -349,7 +842,7 @@ impl Game {
                         fen_string.push_str(&empty_count.to_string());
                         empty_count = 0;
                     }
-                    fen_string.push(square.as_fen_char());
+                    fen_string.push(dialect.content_char(square));
                 }
             }
             if empty_count > 0 {
@@ -363,395 +856,2201 @@ impl Game {
         fen_string
     }
 
-    pub fn valid_moves(&self) -> Vec<Move> {
-        let mut moves = vec![];
+    /// Checks that the position could be held by a physical game set, i.e. no player has more tiles of a type and
+    /// the board holds no more barragoons than the set provides.
+    ///
+    /// # Errors
+    /// Returns a description of the first violated invariant.
+    pub fn check_invariants(&self) -> Result<(), String> {
+        let mut barragoon_count: u8 = 0;
+        let mut tile_counts = HashMap::<Tile, u8>::new();
 
         for square in self.squares() {
-            let mut covered_squares = HashSet::<Coordinate>::new();
-
-            if let SC::Tile(moving_tile) = square.content {
-                let Tile {
-                    tile_type: moving_tile_type,
-                    player: moving_piece_player,
-                } = moving_tile;
-
-                // skip other players pieces
-                if *moving_piece_player != self.current_player {
-                    continue;
-                }
-
-                let all_strides = moving_tile_type.all_strides();
-                for stride in all_strides {
-                    let coordinate_to_cover = square.coordinate + stride.full_delta();
-                    if !Self::contains_coordinate(&coordinate_to_cover) {
-                        // non-existent square
-                        continue;
-                    }
-
-                    if covered_squares.contains(&coordinate_to_cover) {
-                        // already have a way there, don't need to check
-                        continue;
-                    }
-
-                    for full_step in stride.steps() {
-                        let new_coordinate = square.coordinate + full_step.position_delta;
-                        if !Self::contains_coordinate(&new_coordinate) {
-                            //todo(robo) maybe breaking here is fine ... please test this later
-                            continue;
-                        }
-
-                        let target_square_content = self.get_content(&new_coordinate);
-
-                        let is_last_step = full_step.leave_direction.is_none();
-
-                        match target_square_content {
-                            SC::Tile(attacked_tile) => {
-                                let Tile {
-                                    tile_type: _,
-                                    player: colliding_piece_player,
-                                } = attacked_tile;
-                                if (moving_piece_player == colliding_piece_player) || !is_last_step || !stride.can_capture() {
-                                    break;
-                                }
-
-                                moves.push(Move::TileCapture {
-                                    from: (*moving_tile, square.coordinate),
-                                    to: (*attacked_tile, new_coordinate),
-                                });
-                                covered_squares.insert(new_coordinate);
-                            }
-                            SC::Empty => {
-                                if is_last_step {
-                                    moves.push(Move::Straight {
-                                        moving_tile: *moving_tile,
-                                        start: square.coordinate,
-                                        stop: new_coordinate,
-                                    });
-                                    covered_squares.insert(new_coordinate);
-                                }
-                            }
-                            SC::Barragoon(face) => {
-                                if let Some(leave_direction) = full_step.leave_direction {
-                                    if !face.can_be_traversed(full_step.enter_direction, leave_direction) {
-                                        break;
-                                    }
-                                } else if stride.can_capture()
-                                    && face.can_be_captured_by(*moving_tile_type)
-                                    && face.can_be_captured_from(&full_step.enter_direction)
-                                {
-                                    moves.push(Move::BarragoonCapture {
-                                        start: square.coordinate,
-                                        stop: new_coordinate,
-                                    });
-                                    covered_squares.insert(new_coordinate);
-                                } else {
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
+            match square.content {
+                SC::Tile(tile) => *tile_counts.entry(*tile).or_insert(0) += 1,
+                SC::Barragoon(_) => barragoon_count += 1,
+                SC::Empty => (),
             }
         }
-        moves
-    }
-}
 
-impl std::fmt::Display for Game {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "  ")?;
-        for _ in 0..BOARD_WIDTH {
-            write!(f, "+---")?;
+        if barragoon_count > self.rules.barragoon_supply {
+            return Err(format!(
+                "Board holds {barragoon_count} barragoons, but only {} exist.",
+                self.rules.barragoon_supply
+            ));
         }
-        writeln!(f, "+")?;
 
-        for irank in (0..BOARD_HEIGHT as usize).rev() {
-            let rank = self.board[irank];
-            f.write_fmt(format_args!("{} ", RANK_NAMES[irank]))?;
-            for square in rank {
-                write!(f, "| ")?;
-                f.write_fmt(format_args!("{}", square.as_fen_char()))?;
-                write!(f, " ")?;
-            }
-            write!(f, "|\n  ")?;
-            for _ in 0..BOARD_WIDTH {
-                write!(f, "+---")?;
+        for (tile, count) in tile_counts {
+            let supply = tile.tile_type.supply_per_player();
+            if count > supply {
+                return Err(format!(
+                    "Board holds {count} '{}' tiles, but a player only owns {supply}.",
+                    tile.as_fen_char()
+                ));
             }
-            writeln!(f, "+")?;
-        }
-
-        write!(f, "  ")?;
-        for name_of_file in FILE_NAMES {
-            f.write_fmt(format_args!("  {name_of_file} "))?;
         }
 
-        write!(f, "")
+        Ok(())
     }
-}
 
-const RANK_NAMES: [char; BOARD_HEIGHT as usize] = ['1', '2', '3', '4', '5', '6', '7', '8', '9'];
-const FILE_NAMES: [char; BOARD_WIDTH as usize] = ['a', 'b', 'c', 'd', 'e', 'f', 'g'];
+    /// Best-effort check whether the position could arise in play: the piece supply is respected and both players
+    /// still have tiles on the board.
+    #[allow(dead_code)]
+    pub fn looks_playable(&self) -> bool {
+        let has_tiles = |player: Player| {
+            self.squares()
+                .any(|square| matches!(square.content, SC::Tile(tile) if tile.player == player))
+        };
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-enum Move {
-    Straight {
-        moving_tile: Tile,
-        start: Coordinate,
-        stop: Coordinate,
-    },
-    TileCapture {
-        from: (Tile, Coordinate),
-        to: (Tile, Coordinate),
-    },
-    BarragoonCapture {
-        start: Coordinate,
-        stop: Coordinate,
-    },
-}
+        self.check_invariants().is_ok() && has_tiles(Player::White) && has_tiles(Player::Brown)
+    }
 
-impl std::fmt::Display for Move {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if let Self::Straight { moving_tile, start, stop } = self {
-            f.write_fmt(format_args!("{}{}{}", moving_tile.as_fen_char(), start, stop))?;
-        } else if let Self::TileCapture {
-            from: (attacker, start),
-            to: (victim, stop),
-        } = self
-        {
-            f.write_fmt(format_args!("{}{}x{}{}", attacker.as_fen_char(), start, victim.as_fen_char(), stop))?;
+    /// The position with the files reversed, so that the a-file becomes the g-file and vice versa.
+    #[allow(dead_code)]
+    pub fn mirror_files(&self) -> Self {
+        let mut mirrored = *self;
+        for square in self.squares() {
+            let mirrored_content = match square.content {
+                SC::Barragoon(face) => SC::Barragoon(face.mirror_files()),
+                content => *content,
+            };
+            mirrored.set_content(&square.coordinate.mirror_files(), mirrored_content);
         }
+        mirrored
+    }
 
-        write!(f, "")
+    #[allow(dead_code)]
+    pub fn position_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
     }
-}
 
-fn main() {
-    println!("Hello, world!");
+    /// Hash shared by a position and its mirror image, e.g. to treat both as one opening book entry.
+    #[allow(dead_code)]
+    pub fn canonical_hash(&self) -> u64 {
+        self.position_hash().min(self.mirror_files().position_hash())
+    }
 
-    let game = Game::new();
-    println!("{game:?}");
-    println!("{game}");
-    println!("{INITIAL_FEN_STRING}");
-    println!("{}", game.as_fen());
+    /// Number of squares whose content differs between `self` and `other`.
+    #[allow(dead_code)]
+    pub fn square_difference_count(&self, other: &Self) -> u8 {
+        u8::try_from(self.diff(other).len()).expect("Board has more squares than fit into a u8.")
+    }
 
-    for tile_move in game.valid_moves() {
-        println!("{tile_move}");
+    /// Game phase derived from the tile material left on the board, ranging from `u8::MAX` at the start of the game
+    /// down to 0 once no tiles remain.
+    #[allow(dead_code)]
+    pub fn phase(&self) -> u8 {
+        let full_material: u32 = TileType::iter()
+            .map(|tile_type| 2 * u32::from(tile_type.supply_per_player()) * u32::from(tile_type.full_stride_length()))
+            .sum();
+        let material: u32 = self
+            .squares()
+            .filter_map(|square| match square.content {
+                SC::Tile(tile) => Some(u32::from(tile.tile_type.full_stride_length())),
+                _ => None,
+            })
+            .sum();
+
+        u8::try_from(material.min(full_material) * u32::from(u8::MAX) / full_material).unwrap_or(u8::MAX)
     }
 
-    println!("{:?}", TileType::Three.full_strides());
+    /// The board placement with one line per rank, top rank first, and a `.` for every empty square.
+    #[allow(dead_code)]
+    pub fn pretty_fen(&self) -> String {
+        self.board
+            .iter()
+            .rev()
+            .map(|row| {
+                row.iter()
+                    .map(|square| if *square == SC::Empty { '.' } else { square.as_fen_char() })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
 
-    let mut buf_stdin = BufReader::new(io::stdin());
+    /// The valid moves in a fixed order: tiles in board order from a1 rank by rank, and the moves of each tile in the
+    /// order of its strides. The same position always yields the same list.
+    pub fn valid_moves(&self) -> Vec<Move> {
+        self.moves_iter().collect()
+    }
 
-    run_loop(&mut buf_stdin, &mut io::stdout()).expect("Something went wrong while reading input.");
-}
+    /// The valid move written as `notation`, as `Move` displays it, or `None` if no valid move is written that way.
+    pub fn parse_move(&self, notation: &str) -> Option<Move> {
+        self.moves_iter().find(|candidate| candidate.to_string() == notation)
+    }
 
-#[cfg(test)]
-mod tests {
-    use std::collections::HashSet;
+    /// Replaces the contents of `buf` with the valid moves, so a caller can reuse one allocation for many positions.
+    #[allow(dead_code)]
+    pub fn generate_moves_into(&self, buf: &mut Vec<Move>) {
+        buf.clear();
+        buf.extend(self.moves_iter());
+    }
 
-    use super::*;
+    /// Every valid move together with the FEN of the position it leads to.
+    #[allow(dead_code)]
+    pub fn moves_with_results(&self) -> Vec<(Move, String)> {
+        self.moves_iter()
+            .map(|tile_move| {
+                let mut after = *self;
+                after.make_move(&tile_move);
+                (tile_move, after.as_fen())
+            })
+            .collect()
+    }
 
-    #[test]
-    fn empty_game_is_empty() {
-        let game = Game::empty();
+    /// Valid moves ending on the goal rank of the current player.
+    #[allow(dead_code)]
+    pub fn goal_moves(&self) -> Vec<Move> {
+        let goal_rank = self.current_player.goal_rank();
+        self.moves_iter().filter(|tile_move| tile_move.stop().rank == goal_rank).collect()
+    }
 
-        for row in game.board {
-            for cell in row {
-                assert_eq!(cell, SquareContent::Empty);
+    /// Squares of the tiles of `player` that have at least one valid move onto their goal rank.
+    #[allow(dead_code)]
+    pub fn goal_threats(&self, player: Player) -> Vec<Coordinate> {
+        let mut game = *self;
+        game.current_player = player;
+
+        let mut threats = Vec::new();
+        for tile_move in game.goal_moves() {
+            if !threats.contains(&tile_move.start()) {
+                threats.push(tile_move.start());
             }
         }
+        threats
     }
 
-    #[test]
-    fn game_startpos_according_to_rules() {
-        let game = Game::new();
-
-        // first rank
-        assert_eq!(*game.get_content(&Coordinate::new(0, 0)), SquareContent::Empty);
-        assert_eq!(
-            *game.get_content(&Coordinate::new(0, 1)),
-            SquareContent::Tile(Tile {
-                tile_type: TileType::Four,
-                player: Player::White
-            })
-        );
-        assert_eq!(
-            *game.get_content(&Coordinate::new(0, 2)),
-            SquareContent::Tile(Tile {
-                tile_type: TileType::Three,
-                player: Player::White
+    /// Valid moves after which the mover has a tile that could reach its goal rank with its following move.
+    #[allow(dead_code)]
+    pub fn winning_threats(&self) -> Vec<Move> {
+        let mover = self.current_player;
+        self.moves_iter()
+            .filter(|tile_move| {
+                let mut after = *self;
+                after.make_move(tile_move);
+                !after.goal_threats(mover).is_empty()
             })
-        );
-        assert_eq!(*game.get_content(&Coordinate::new(0, 3)), SquareContent::Empty);
-        assert_eq!(
-            *game.get_content(&Coordinate::new(0, 4)),
-            SquareContent::Tile(Tile {
-                tile_type: TileType::Three,
-                player: Player::White
-            })
-        );
-        assert_eq!(
-            *game.get_content(&Coordinate::new(0, 5)),
-            SquareContent::Tile(Tile {
-                tile_type: TileType::Four,
-                player: Player::White
+            .collect()
+    }
+
+    /// Valid moves after which the opponent has no tile that could reach its goal rank with its next move.
+    #[allow(dead_code)]
+    pub fn safe_moves(&self) -> Vec<Move> {
+        let opponent = self.current_player.opponent();
+        self.moves_iter()
+            .filter(|tile_move| {
+                let mut after = *self;
+                after.make_move(tile_move);
+                after.goal_threats(opponent).is_empty()
             })
-        );
-        assert_eq!(*game.get_content(&Coordinate::new(0, 6)), SquareContent::Empty);
+            .collect()
+    }
 
-        // second rank
-        assert_eq!(*game.get_content(&Coordinate::new(1, 0)), SquareContent::Empty);
-        assert_eq!(*game.get_content(&Coordinate::new(1, 1)), SquareContent::Empty);
-        assert_eq!(
-            *game.get_content(&Coordinate::new(1, 2)),
-            SquareContent::Tile(Tile {
-                tile_type: TileType::Two,
-                player: Player::White
+    /// Every valid move capturing the opponent's tile on `victim`.
+    #[allow(dead_code)]
+    pub fn moves_capturing(&self, victim: &Coordinate) -> Vec<Move> {
+        self.moves_iter()
+            .filter(|tile_move| tile_move.kind() == MoveKind::TileCapture && tile_move.stop() == *victim)
+            .collect()
+    }
+
+    /// The captures of tiles and barragoons the opponent can answer `m` with.
+    #[allow(dead_code)]
+    pub fn opponent_captures_after(&self, m: &Move) -> Vec<Move> {
+        let mut after = *self;
+        after.make_move(m);
+        after.moves_iter().filter(|reply| reply.kind() != MoveKind::Quiet).collect()
+    }
+
+    /// Moves after which the opponent can no longer capture the current player's tile on `hanging`, whether by moving
+    /// the tile away, capturing the attacker or blocking its path.
+    #[allow(dead_code)]
+    pub fn defensive_moves(&self, hanging: &Coordinate) -> Vec<Move> {
+        self.moves_iter()
+            .filter(|defence| {
+                let tile_square = if defence.start() == *hanging { defence.stop() } else { *hanging };
+                !self
+                    .opponent_captures_after(defence)
+                    .iter()
+                    .any(|reply| reply.kind() == MoveKind::TileCapture && reply.stop() == tile_square)
             })
-        );
-        assert_eq!(
-            *game.get_content(&Coordinate::new(1, 3)),
-            SquareContent::Tile(Tile {
-                tile_type: TileType::Three,
-                player: Player::White
+            .collect()
+    }
+
+    /// What `m` would capture: the tile or barragoon on its destination for captures, `None` for quiet moves.
+    #[allow(dead_code)]
+    pub const fn capture_victim(&self, m: &Move) -> Option<SquareContent> {
+        match m.kind() {
+            MoveKind::Quiet => None,
+            MoveKind::TileCapture | MoveKind::BarragoonCapture => Some(*self.get_content(&m.stop())),
+        }
+    }
+
+    /// Whether `coord` holds a tile of the current player that has no valid move.
+    #[allow(dead_code)]
+    pub fn is_trapped(&self, coord: &Coordinate) -> bool {
+        matches!(self.get_content(coord), SC::Tile(tile) if tile.player == self.current_player)
+            && !self.moves_iter().any(|tile_move| tile_move.start() == *coord)
+    }
+
+    /// Valid moves that start and stop inside the region spanned by `ranks` and `files`.
+    #[allow(dead_code)]
+    pub fn moves_within(&self, ranks: Range<u8>, files: Range<u8>) -> Vec<Move> {
+        let inside = |square: Coordinate| ranks.contains(&square.rank) && files.contains(&square.file);
+        self.moves_iter()
+            .filter(|tile_move| inside(tile_move.start()) && inside(tile_move.stop()))
+            .collect()
+    }
+
+    /// All barragoons on the board, in board order.
+    #[allow(dead_code)]
+    pub fn barragoons(&self) -> Vec<(Coordinate, BarragoonFace)> {
+        self.squares()
+            .filter_map(|square| match square.content {
+                SC::Barragoon(face) => Some((square.coordinate, *face)),
+                _ => None,
             })
-        );
-        assert_eq!(
-            *game.get_content(&Coordinate::new(1, 4)),
-            SquareContent::Tile(Tile {
-                tile_type: TileType::Two,
-                player: Player::White
+            .collect()
+    }
+
+    /// Barragoons that no tile of either player can capture in the current position.
+    #[allow(dead_code)]
+    pub fn immovable_barragoons(&self) -> Vec<Coordinate> {
+        let mut capturable = HashSet::new();
+        for player in [Player::White, Player::Brown] {
+            let mut game = *self;
+            game.current_player = player;
+            capturable.extend(game.capturable_barragoons().into_iter().map(|(coordinate, _)| coordinate));
+        }
+
+        self.barragoons()
+            .into_iter()
+            .map(|(coordinate, _)| coordinate)
+            .filter(|coordinate| !capturable.contains(coordinate))
+            .collect()
+    }
+
+    /// Counts the move sequences of length `depth` starting at this position.
+    #[allow(dead_code)]
+    pub fn perft(&self, depth: u8) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        self.moves_iter()
+            .map(|tile_move| {
+                let mut child = *self;
+                child.make_move(&tile_move);
+                child.perft(depth - 1)
             })
-        );
-        assert_eq!(*game.get_content(&Coordinate::new(1, 5)), SquareContent::Empty);
-        assert_eq!(*game.get_content(&Coordinate::new(1, 6)), SquareContent::Empty);
+            .sum()
+    }
 
-        // third rank
-        for file in 0..7 {
-            assert_eq!(*game.get_content(&Coordinate::new(2, file)), SquareContent::Empty);
+    /// Same as `perft`, but remembers the counts of positions already visited in `cache`, so transpositions are only
+    /// counted once. The cache can be reused across calls for the same game.
+    #[allow(dead_code)]
+    pub fn perft_hashed(&self, depth: u8, cache: &mut HashMap<(Self, u8), u64>) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        if let Some(count) = cache.get(&(*self, depth)) {
+            return *count;
         }
 
-        // forth rank
-        assert_eq!(*game.get_content(&Coordinate::new(3, 0)), SquareContent::Empty);
-        assert_eq!(
-            *game.get_content(&Coordinate::new(3, 1)),
-            SquareContent::Barragoon(BarragoonFace::Blocking)
-        );
-        assert_eq!(*game.get_content(&Coordinate::new(3, 2)), SquareContent::Empty);
-        assert_eq!(*game.get_content(&Coordinate::new(3, 3)), SquareContent::Empty);
-        assert_eq!(*game.get_content(&Coordinate::new(3, 4)), SquareContent::Empty);
-        assert_eq!(
-            *game.get_content(&Coordinate::new(3, 5)),
-            SquareContent::Barragoon(BarragoonFace::Blocking)
-        );
-        assert_eq!(*game.get_content(&Coordinate::new(3, 6)), SquareContent::Empty);
+        let mut count = 0;
+        for tile_move in self.moves_iter() {
+            let mut child = *self;
+            child.make_move(&tile_move);
+            count += child.perft_hashed(depth - 1, cache);
+        }
 
-        // fifth rank
-        assert_eq!(
-            *game.get_content(&Coordinate::new(4, 0)),
-            SquareContent::Barragoon(BarragoonFace::Blocking)
-        );
-        assert_eq!(*game.get_content(&Coordinate::new(4, 1)), SquareContent::Empty);
-        assert_eq!(
-            *game.get_content(&Coordinate::new(4, 2)),
-            SquareContent::Barragoon(BarragoonFace::Blocking)
-        );
-        assert_eq!(*game.get_content(&Coordinate::new(4, 3)), SquareContent::Empty);
-        assert_eq!(
-            *game.get_content(&Coordinate::new(4, 4)),
-            SquareContent::Barragoon(BarragoonFace::Blocking)
-        );
-        assert_eq!(*game.get_content(&Coordinate::new(4, 5)), SquareContent::Empty);
-        assert_eq!(
-            *game.get_content(&Coordinate::new(4, 6)),
-            SquareContent::Barragoon(BarragoonFace::Blocking)
-        );
+        cache.insert((*self, depth), count);
+        count
+    }
 
-        // sixth rank
-        assert_eq!(*game.get_content(&Coordinate::new(5, 0)), SquareContent::Empty);
-        assert_eq!(
-            *game.get_content(&Coordinate::new(5, 1)),
-            SquareContent::Barragoon(BarragoonFace::Blocking)
-        );
-        assert_eq!(*game.get_content(&Coordinate::new(5, 2)), SquareContent::Empty);
-        assert_eq!(*game.get_content(&Coordinate::new(5, 3)), SquareContent::Empty);
-        assert_eq!(*game.get_content(&Coordinate::new(5, 4)), SquareContent::Empty);
-        assert_eq!(
-            *game.get_content(&Coordinate::new(5, 5)),
-            SquareContent::Barragoon(BarragoonFace::Blocking)
-        );
-        assert_eq!(*game.get_content(&Coordinate::new(5, 6)), SquareContent::Empty);
+    /// Barragoons the current player can capture this turn, in board order.
+    #[allow(dead_code)]
+    pub fn capturable_barragoons(&self) -> Vec<(Coordinate, BarragoonFace)> {
+        let mut barragoons = vec![];
 
-        // seventh rank
-        for file in 0..7 {
-            assert_eq!(*game.get_content(&Coordinate::new(6, file)), SquareContent::Empty);
+        for tile_move in self.moves_iter() {
+            if let Move::BarragoonCapture { stop, .. } = tile_move {
+                if let SC::Barragoon(face) = self.get_content(&stop) {
+                    if !barragoons.contains(&(stop, *face)) {
+                        barragoons.push((stop, *face));
+                    }
+                }
+            }
         }
 
-        // eigth rank
-        assert_eq!(*game.get_content(&Coordinate::new(7, 0)), SquareContent::Empty);
-        assert_eq!(*game.get_content(&Coordinate::new(7, 1)), SquareContent::Empty);
-        assert_eq!(
-            *game.get_content(&Coordinate::new(7, 2)),
-            SquareContent::Tile(Tile {
-                tile_type: TileType::Two,
-                player: Player::Brown
-            })
-        );
-        assert_eq!(
-            *game.get_content(&Coordinate::new(7, 3)),
-            SquareContent::Tile(Tile {
-                tile_type: TileType::Three,
-                player: Player::Brown
-            })
-        );
-        assert_eq!(
-            *game.get_content(&Coordinate::new(7, 4)),
-            SquareContent::Tile(Tile {
-                tile_type: TileType::Two,
-                player: Player::Brown
-            })
-        );
-        assert_eq!(*game.get_content(&Coordinate::new(7, 5)), SquareContent::Empty);
-        assert_eq!(*game.get_content(&Coordinate::new(7, 6)), SquareContent::Empty);
+        barragoons
+    }
 
-        // ninth rank
-        assert_eq!(*game.get_content(&Coordinate::new(8, 0)), SquareContent::Empty);
-        assert_eq!(
-            *game.get_content(&Coordinate::new(8, 1)),
-            SquareContent::Tile(Tile {
-                tile_type: TileType::Four,
-                player: Player::Brown
-            })
-        );
-        assert_eq!(
-            *game.get_content(&Coordinate::new(8, 2)),
-            SquareContent::Tile(Tile {
-                tile_type: TileType::Three,
-                player: Player::Brown
-            })
-        );
-        assert_eq!(*game.get_content(&Coordinate::new(8, 3)), SquareContent::Empty);
-        assert_eq!(
-            *game.get_content(&Coordinate::new(8, 4)),
-            SquareContent::Tile(Tile {
-                tile_type: TileType::Three,
-                player: Player::Brown
+    /// Squares `player` could move a tile to if it were their turn.
+    #[allow(dead_code)]
+    pub fn reachable_squares(&self, player: Player) -> HashSet<Coordinate> {
+        let mut game = *self;
+        game.current_player = player;
+        game.moves_iter().map(|tile_move| tile_move.stop()).collect()
+    }
+
+    /// Squares of the current player's tiles that can move to the empty square `target` without capturing, in board
+    /// order.
+    #[allow(dead_code)]
+    pub fn reachers_of(&self, target: &Coordinate) -> Vec<Coordinate> {
+        let mut reachers: Vec<Coordinate> = self
+            .moves_iter()
+            .filter_map(|tile_move| match tile_move {
+                Move::Straight { start, stop, .. } if stop == *target => Some(start),
+                _ => None,
             })
-        );
-        assert_eq!(
-            *game.get_content(&Coordinate::new(8, 5)),
-            SquareContent::Tile(Tile {
-                tile_type: TileType::Four,
-                player: Player::Brown
+            .collect();
+        reachers.dedup();
+        reachers
+    }
+
+    /// A one-line digest of the position for logs: FEN, side to move, number of valid moves and material balance.
+    /// Material counts every tile with its full stride length, positive values favour White.
+    #[allow(dead_code)]
+    pub fn summary(&self) -> String {
+        let material: i32 = self
+            .squares()
+            .filter_map(|square| match square.content {
+                SC::Tile(tile) => {
+                    let value = i32::from(tile.tile_type.full_stride_length());
+                    Some(if tile.player == Player::White { value } else { -value })
+                }
+                _ => None,
             })
-        );
-        assert_eq!(*game.get_content(&Coordinate::new(8, 6)), SquareContent::Empty);
+            .sum();
+
+        format!(
+            "{} {:?} moves {} material {material:+}",
+            self.as_fen(),
+            self.current_player,
+            self.moves_iter().count()
+        )
     }
 
-    #[test]
-    fn initial_gamestate_allowed_moves() {
-        let moves = Game::new().valid_moves();
-        assert_eq!(moves.len(), 28);
+    /// Number of valid moves of the current player minus the number the opponent would have if it were their turn.
+    #[allow(dead_code)]
+    pub fn mobility_balance(&self) -> i32 {
+        let move_count = |player: Player| {
+            let mut game = *self;
+            game.current_player = player;
+            i32::try_from(game.moves_iter().count()).expect("Move count fits into i32.")
+        };
+
+        move_count(self.current_player) - move_count(self.current_player.opponent())
+    }
+
+    /// Marks every square White alone can reach with +1 and every square Brown alone can reach with -1. Squares
+    /// reached by both players or by none are 0.
+    #[allow(dead_code)]
+    pub fn territory(&self) -> [[i8; BOARD_WIDTH as usize]; BOARD_HEIGHT as usize] {
+        let white_reach = self.reachable_squares(Player::White);
+        let brown_reach = self.reachable_squares(Player::Brown);
+        let mut territory = [[0; BOARD_WIDTH as usize]; BOARD_HEIGHT as usize];
+
+        for square in self.squares() {
+            let Coordinate { rank, file } = square.coordinate;
+            territory[rank as usize][file as usize] =
+                i8::from(white_reach.contains(&square.coordinate)) - i8::from(brown_reach.contains(&square.coordinate));
+        }
+
+        territory
+    }
+
+    /// Generates the valid moves lazily, one tile at a time.
+    pub fn moves_iter(&self) -> impl Iterator<Item = Move> + '_ {
+        let captures_only = self.rules.mandatory_capture && self.has_capture();
+        self.unrestricted_moves()
+            .filter(move |tile_move| !captures_only || tile_move.kind() != MoveKind::Quiet)
+    }
+
+    /// Whether the current player can capture a tile or barragoon. Stops at the first capture found.
+    pub fn has_capture(&self) -> bool {
+        self.unrestricted_moves().any(|tile_move| tile_move.kind() != MoveKind::Quiet)
+    }
+
+    /// The moves the tiles could make if no rule restricted the choice among them.
+    fn unrestricted_moves(&self) -> impl Iterator<Item = Move> + '_ {
+        self.squares().flat_map(|square| self.tile_moves(&square))
+    }
+
+    /// Valid moves of the tile on `square`, if it belongs to the current player.
+    fn tile_moves(&self, square: &SquareView) -> Vec<Move> {
+        let mut moves = vec![];
+        let mut covered_squares = HashSet::<Coordinate>::new();
+
+        let SC::Tile(moving_tile) = square.content else {
+            return moves;
+        };
+
+        // skip other players pieces
+        if moving_tile.player != self.current_player {
+            return moves;
+        }
+
+        let all_strides = moving_tile.tile_type.all_strides();
+        for stride in all_strides {
+            let coordinate_to_cover = square.coordinate + stride.full_delta();
+            if !Self::contains_coordinate(&coordinate_to_cover) {
+                // non-existent square
+                continue;
+            }
+
+            if covered_squares.contains(&coordinate_to_cover) {
+                // already have a way there, don't need to check
+                continue;
+            }
+
+            if let Some(stride_move) = self.stride_move(square.coordinate, moving_tile, &stride) {
+                covered_squares.insert(stride_move.stop());
+                moves.push(stride_move);
+            }
+        }
+
+        moves
+    }
+
+    /// The move resulting from walking `stride` with `moving_tile` from `origin`, if the stride is not blocked.
+    fn stride_move(&self, origin: Coordinate, moving_tile: &Tile, stride: &Stride) -> Option<Move> {
+        for full_step in stride.steps() {
+            let new_coordinate = full_step.square_from(origin);
+            if !Self::contains_coordinate(&new_coordinate) {
+                //todo(robo) maybe breaking here is fine ... please test this later
+                continue;
+            }
+
+            let is_last_step = full_step.leave_direction.is_none();
+
+            let target_square_content = match self.get_content(&new_coordinate) {
+                // without barragoon mechanics tiles pass over barragoons, but must not land on and remove them
+                SC::Barragoon(_) if !self.rules.barragoons_enabled && is_last_step => return None,
+                SC::Barragoon(_) if !self.rules.barragoons_enabled => &SC::Empty,
+                content => content,
+            };
+
+            match target_square_content {
+                SC::Tile(attacked_tile) => {
+                    if (moving_tile.player == attacked_tile.player) || !is_last_step || !stride.can_capture() {
+                        return None;
+                    }
+
+                    return Some(Move::TileCapture {
+                        from: (*moving_tile, origin),
+                        to: (*attacked_tile, new_coordinate),
+                    });
+                }
+                SC::Empty => {
+                    if is_last_step {
+                        return Some(Move::Straight {
+                            moving_tile: *moving_tile,
+                            start: origin,
+                            stop: new_coordinate,
+                        });
+                    }
+                }
+                SC::Barragoon(face) => {
+                    if let Some(leave_direction) = full_step.leave_direction {
+                        if !face.can_be_traversed(full_step.enter_direction, leave_direction) {
+                            return None;
+                        }
+                    } else if stride.can_capture()
+                        && face.can_be_captured_by(moving_tile.tile_type)
+                        && face.can_be_captured_from(&full_step.enter_direction)
+                    {
+                        return Some(Move::BarragoonCapture {
+                            start: origin,
+                            stop: new_coordinate,
+                        });
+                    } else {
+                        return None;
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// The squares the tile passes in order when playing `m`, ending with the destination and leaving out the start.
+    /// Barragoons on the way are included, as the stride found for `m` crosses them according to their faces. Empty if
+    /// `m` is not a valid move.
+    #[allow(dead_code)]
+    pub fn path_squares(&self, m: &Move) -> Vec<Coordinate> {
+        let (start, stop) = (m.start(), m.stop());
+        let SC::Tile(moving_tile) = *self.get_content(&start) else {
+            return vec![];
+        };
+        if !self.is_legal(m) {
+            return vec![];
+        }
+
+        moving_tile
+            .tile_type
+            .all_strides()
+            .iter()
+            .filter(|stride| stride.full_delta() == stop - start)
+            .find(|stride| self.stride_move(start, &moving_tile, stride) == Some(*m))
+            .map(|stride| stride.steps().map(|step| step.square_from(start)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether `tile_move` is one of the valid moves of the position. Straight moves are checked by walking only the
+    /// strides leading to their destination; all other moves are looked up in the generated moves.
+    #[allow(dead_code)]
+    pub fn is_legal(&self, tile_move: &Move) -> bool {
+        let Move::Straight { moving_tile, start, stop } = *tile_move else {
+            return self.moves_iter().any(|valid_move| valid_move == *tile_move);
+        };
+
+        if self.rules.mandatory_capture {
+            return self.moves_iter().any(|valid_move| valid_move == *tile_move);
+        }
+
+        if !Self::contains_coordinate(&start)
+            || !Self::contains_coordinate(&stop)
+            || moving_tile.player != self.current_player
+            || *self.get_content(&start) != SC::Tile(moving_tile)
+        {
+            return false;
+        }
+
+        let delta = stop - start;
+        moving_tile
+            .tile_type
+            .all_strides()
+            .iter()
+            .filter(|stride| stride.full_delta() == delta)
+            .any(|stride| self.stride_move(start, &moving_tile, stride) == Some(*tile_move))
+    }
+}
+
+impl FromIterator<(Coordinate, SquareContent)> for Game {
+    fn from_iter<I: IntoIterator<Item = (Coordinate, SquareContent)>>(iter: I) -> Self {
+        Self::from_squares(iter)
+    }
+}
+
+impl std::fmt::Display for Game {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "  ")?;
+        for _ in 0..BOARD_WIDTH {
+            write!(f, "+---")?;
+        }
+        writeln!(f, "+")?;
+
+        for irank in (0..BOARD_HEIGHT as usize).rev() {
+            let rank = self.board[irank];
+            f.write_fmt(format_args!("{} ", RANK_NAMES[irank]))?;
+            for square in rank {
+                write!(f, "| ")?;
+                f.write_fmt(format_args!("{}", square.as_fen_char()))?;
+                write!(f, " ")?;
+            }
+            write!(f, "|\n  ")?;
+            for _ in 0..BOARD_WIDTH {
+                write!(f, "+---")?;
+            }
+            writeln!(f, "+")?;
+        }
+
+        write!(f, "  ")?;
+        for name_of_file in FILE_NAMES {
+            f.write_fmt(format_args!("  {name_of_file} "))?;
+        }
+
+        write!(f, "")
+    }
+}
+
+const RANK_NAMES: [char; BOARD_HEIGHT as usize] = ['1', '2', '3', '4', '5', '6', '7', '8', '9'];
+const FILE_NAMES: [char; BOARD_WIDTH as usize] = ['a', 'b', 'c', 'd', 'e', 'f', 'g'];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Move {
+    Straight {
+        moving_tile: Tile,
+        start: Coordinate,
+        stop: Coordinate,
+    },
+    TileCapture {
+        from: (Tile, Coordinate),
+        to: (Tile, Coordinate),
+    },
+    BarragoonCapture {
+        start: Coordinate,
+        stop: Coordinate,
+    },
+}
+
+/// What a move does, without the squares and tiles involved.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum MoveKind {
+    Quiet,
+    TileCapture,
+    BarragoonCapture,
+}
+
+impl Move {
+    pub const fn kind(&self) -> MoveKind {
+        match self {
+            Self::Straight { .. } => MoveKind::Quiet,
+            Self::TileCapture { .. } => MoveKind::TileCapture,
+            Self::BarragoonCapture { .. } => MoveKind::BarragoonCapture,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub const fn start(&self) -> Coordinate {
+        match *self {
+            Self::Straight { start, .. } | Self::TileCapture { from: (_, start), .. } | Self::BarragoonCapture { start, .. } => start,
+        }
+    }
+
+    pub const fn stop(&self) -> Coordinate {
+        match *self {
+            Self::Straight { stop, .. } | Self::TileCapture { to: (_, stop), .. } | Self::BarragoonCapture { stop, .. } => stop,
+        }
+    }
+
+    /// Packs the move into a `u32`. From the least significant bit on, the layout is
+    ///
+    /// | bits  | content                                                            |
+    /// |-------|--------------------------------------------------------------------|
+    /// | 0-1   | variant: 0 = `Straight`, 1 = `TileCapture`, 2 = `BarragoonCapture` |
+    /// | 2-7   | `Coordinate::index` of the start square                            |
+    /// | 8-13  | `Coordinate::index` of the stop square                             |
+    /// | 14-16 | `Tile::index` of the moving tile (0 for `BarragoonCapture`)        |
+    /// | 17-19 | `Tile::index` of the captured tile (0 unless `TileCapture`)        |
+    ///
+    /// All remaining bits are 0.
+    #[allow(dead_code)]
+    pub const fn to_u32(self) -> u32 {
+        let (tag, moving_tile, captured_tile) = match self {
+            Self::Straight { moving_tile, .. } => (0, moving_tile.index(), 0),
+            Self::TileCapture {
+                from: (attacker, _),
+                to: (victim, _),
+            } => (1, attacker.index(), victim.index()),
+            Self::BarragoonCapture { .. } => (2, 0, 0),
+        };
+
+        tag | (self.start().index() as u32) << 2 | (self.stop().index() as u32) << 8 | moving_tile << 14 | captured_tile << 17
+    }
+
+    /// Inverse of `to_u32`. Returns `None` if `code` does not describe a move.
+    #[allow(dead_code)]
+    pub fn from_u32(code: u32) -> Option<Self> {
+        if code >> 20 != 0 {
+            return None;
+        }
+
+        let square = |shift: u32| Coordinate::from_index(u8::try_from((code >> shift) & 0b11_1111).ok()?);
+        let (start, stop) = (square(2)?, square(8)?);
+        let (moving_tile, captured_tile) = ((code >> 14) & 0b111, (code >> 17) & 0b111);
+
+        match code & 0b11 {
+            0 if captured_tile == 0 => Some(Self::Straight {
+                moving_tile: Tile::from_index(moving_tile)?,
+                start,
+                stop,
+            }),
+            1 => Some(Self::TileCapture {
+                from: (Tile::from_index(moving_tile)?, start),
+                to: (Tile::from_index(captured_tile)?, stop),
+            }),
+            2 if moving_tile == 0 && captured_tile == 0 => Some(Self::BarragoonCapture { start, stop }),
+            _ => None,
+        }
+    }
+
+    /// The same move on the board mirrored along the files, see `Game::mirror_files`.
+    #[allow(dead_code)]
+    pub const fn mirror_files(&self) -> Self {
+        match *self {
+            Self::Straight { moving_tile, start, stop } => Self::Straight {
+                moving_tile,
+                start: start.mirror_files(),
+                stop: stop.mirror_files(),
+            },
+            Self::TileCapture {
+                from: (attacker, start),
+                to: (victim, stop),
+            } => Self::TileCapture {
+                from: (attacker, start.mirror_files()),
+                to: (victim, stop.mirror_files()),
+            },
+            Self::BarragoonCapture { start, stop } => Self::BarragoonCapture {
+                start: start.mirror_files(),
+                stop: stop.mirror_files(),
+            },
+        }
+    }
+
+    /// All squares the move touches, starting with the square the tile leaves.
+    #[allow(dead_code)]
+    pub fn squares(&self) -> Vec<Coordinate> {
+        vec![self.start(), self.stop()]
+    }
+}
+
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Self::Straight { moving_tile, start, stop } = self {
+            f.write_fmt(format_args!("{}{}{}", moving_tile.as_fen_char(), start, stop))?;
+        } else if let Self::TileCapture {
+            from: (attacker, start),
+            to: (victim, stop),
+        } = self
+        {
+            f.write_fmt(format_args!("{}{}x{}{}", attacker.as_fen_char(), start, victim.as_fen_char(), stop))?;
+        } else if let Self::BarragoonCapture { start, stop } = self {
+            f.write_fmt(format_args!("{start}x{stop}"))?;
+        }
+
+        write!(f, "")
+    }
+}
+
+fn main() {
+    if std::env::args().nth(1).as_deref() == Some("replay") {
+        let illegal_line = replay(&mut io::stdin().lock(), &mut io::stdout()).expect("Something went wrong while replaying moves.");
+        if illegal_line.is_some() {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if std::env::args().nth(1).as_deref() == Some("moves") {
+        let fen = std::env::args().skip(2).collect::<Vec<String>>().join(" ");
+        let listed = list_moves(&fen, &mut io::stdout()).expect("Something went wrong while listing moves.");
+        if let Err(error) = listed {
+            eprintln!("Invalid FEN '{fen}': {error}.");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    println!("Hello, world!");
+
+    let game = Game::new();
+    println!("{game:?}");
+    println!("{game}");
+    println!("{INITIAL_FEN_STRING}");
+    println!("{}", game.as_fen());
+
+    for tile_move in game.valid_moves() {
+        println!("{tile_move}");
+    }
+
+    println!("{:?}", TileType::Three.full_strides());
+
+    let mut buf_stdin = BufReader::new(io::stdin());
+
+    run_loop(&mut buf_stdin, &mut io::stdout()).expect("Something went wrong while reading input.");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use proptest::prelude::*;
+    use proptest::sample::Index;
+
+    use super::*;
+
+    /// Plays random valid moves from the start position, stopping early if a player cannot move.
+    fn random_position(choices: &[Index]) -> Game {
+        let mut game = Game::new();
+        for choice in choices {
+            let moves = game.valid_moves();
+            if moves.is_empty() {
+                break;
+            }
+            game.make_move(&moves[choice.index(moves.len())]);
+        }
+        game
+    }
+
+    fn moving_player(game: &Game, tile_move: &Move) -> Option<Player> {
+        match game.get_content(&tile_move.start()) {
+            SC::Tile(tile) => Some(tile.player),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn reachers_of_lists_every_tile_reaching_the_square() {
+        let game = Game::from_fen("7/7/7/7/7/7/7/7/Z1Z4").expect("FEN is valid.");
+        assert_eq!(
+            game.reachers_of(&Coordinate::new(1, 1)),
+            vec![Coordinate::new(0, 0), Coordinate::new(0, 2)]
+        );
+        assert!(game.reachers_of(&Coordinate::new(8, 6)).is_empty());
+    }
+
+    #[test]
+    fn moves_with_results_pair_moves_with_fens() {
+        let game = Game::new();
+        let pairs = game.moves_with_results();
+
+        assert_eq!(pairs.len(), 28);
+        for (tile_move, fen) in pairs {
+            let mut after = game;
+            after.make_move(&tile_move);
+            assert_eq!(Game::from_fen(&fen).expect("FEN is valid."), after);
+        }
+    }
+
+    #[test]
+    fn moves_within_stay_in_the_region() {
+        let game = Game::new();
+        let moves = game.moves_within(0..3, 0..BOARD_WIDTH);
+
+        assert!(!moves.is_empty());
+        assert!(moves.len() < game.valid_moves().len());
+        assert!(moves
+            .iter()
+            .all(|tile_move| tile_move.start().rank < 3 && tile_move.stop().rank < 3));
+        assert!(game.moves_within(3..BOARD_HEIGHT, 0..BOARD_WIDTH).is_empty());
+    }
+
+    #[test]
+    fn moves_never_land_on_friendly_tiles() {
+        let dense_fens = [
+            INITIAL_FEN_STRING,
+            "vdzvdzv/zdvzdvz/vzdvzdv/dvzdvzd/7/DVZDVZD/VZDVZDV/ZDVZDVZ/VDZVDZV",
+            "vdzvdzv/zxvzxvz/dvzdvzd/x1x1x1x/ZvDzVdZ/x1x1x1x/DVZDVZD/ZxVZxVZ/VDZVDZV",
+            "zZzZzZz/ZzZzZzZ/zZzZzZz/ZzZzZzZ/zZzZzZz/ZzZzZzZ/zZzZzZz/ZzZzZzZ/zZzZzZz",
+            "dVdVdVd/VdVdVdV/d+d+d+d/VdVdVdV/dVdVdVd/V-V|V-V/dVdVdVd/VdVdVdV/dVdVdVd",
+        ];
+
+        for fen in dense_fens {
+            for player in [Player::White, Player::Brown] {
+                let mut game = Game::from_fen(fen).expect("FEN is valid.");
+                game.set_current_player(player);
+                for tile_move in game.valid_moves() {
+                    if let SC::Tile(tile) = game.get_content(&tile_move.stop()) {
+                        assert_ne!(tile.player, player, "{tile_move:?} lands on a friendly tile in {fen}");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Compares the sorted notation of the valid moves in `fen` with the lines of `testdata/moves/<expected_moves_file>`.
+    fn assert_moves_match(fen: &str, expected_moves_file: &str) {
+        let path = format!("{}/testdata/moves/{expected_moves_file}", env!("CARGO_MANIFEST_DIR"));
+        let expected = std::fs::read_to_string(&path).expect("Golden file is readable.");
+        let game = Game::from_fen(fen).expect("FEN is valid.");
+        let mut moves: Vec<String> = game.valid_moves().iter().map(ToString::to_string).collect();
+        moves.sort();
+
+        assert_eq!(moves, expected.lines().collect::<Vec<_>>(), "moves of {fen} differ from {path}");
+    }
+
+    #[test]
+    fn moves_match_golden_files() {
+        assert_moves_match(INITIAL_FEN_STRING, "startpos.txt");
+        assert_moves_match("1vd1dv1/2zdz2/7/1<3>1/x1+1-1x/1S1Z1n1/3D3/2Z1Z2/1V3V1", "barragoons.txt");
+    }
+
+    proptest! {
+        #[test]
+        fn playing_valid_moves_keeps_position_sound(choices in prop::collection::vec(any::<Index>(), 0..16)) {
+            let game = random_position(&choices);
+
+            for tile_move in game.valid_moves() {
+                let mut next = game;
+                next.make_move(&tile_move);
+                prop_assert_eq!(next.check_invariants(), Ok(()));
+            }
+        }
+
+        #[test]
+        fn valid_moves_belong_to_side_to_move(choices in prop::collection::vec(any::<Index>(), 0..16)) {
+            let game = random_position(&choices);
+
+            for tile_move in game.valid_moves() {
+                prop_assert_eq!(moving_player(&game, &tile_move), Some(game.current_player()));
+
+                let mut next = game;
+                next.make_move(&tile_move);
+                prop_assert_eq!(next.current_player(), game.current_player().opponent());
+            }
+        }
+    }
+
+    #[test]
+    fn empty_game_is_empty() {
+        let game = Game::empty();
+
+        for row in game.board {
+            for cell in row {
+                assert_eq!(cell, SquareContent::Empty);
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn game_startpos_according_to_rules() {
+        let game = Game::new();
+
+        // first rank
+        assert_eq!(*game.get_content(&Coordinate::new(0, 0)), SquareContent::Empty);
+        assert_eq!(
+            *game.get_content(&Coordinate::new(0, 1)),
+            SquareContent::Tile(Tile {
+                tile_type: TileType::Four,
+                player: Player::White
+            })
+        );
+        assert_eq!(
+            *game.get_content(&Coordinate::new(0, 2)),
+            SquareContent::Tile(Tile {
+                tile_type: TileType::Three,
+                player: Player::White
+            })
+        );
+        assert_eq!(*game.get_content(&Coordinate::new(0, 3)), SquareContent::Empty);
+        assert_eq!(
+            *game.get_content(&Coordinate::new(0, 4)),
+            SquareContent::Tile(Tile {
+                tile_type: TileType::Three,
+                player: Player::White
+            })
+        );
+        assert_eq!(
+            *game.get_content(&Coordinate::new(0, 5)),
+            SquareContent::Tile(Tile {
+                tile_type: TileType::Four,
+                player: Player::White
+            })
+        );
+        assert_eq!(*game.get_content(&Coordinate::new(0, 6)), SquareContent::Empty);
+
+        // second rank
+        assert_eq!(*game.get_content(&Coordinate::new(1, 0)), SquareContent::Empty);
+        assert_eq!(*game.get_content(&Coordinate::new(1, 1)), SquareContent::Empty);
+        assert_eq!(
+            *game.get_content(&Coordinate::new(1, 2)),
+            SquareContent::Tile(Tile {
+                tile_type: TileType::Two,
+                player: Player::White
+            })
+        );
+        assert_eq!(
+            *game.get_content(&Coordinate::new(1, 3)),
+            SquareContent::Tile(Tile {
+                tile_type: TileType::Three,
+                player: Player::White
+            })
+        );
+        assert_eq!(
+            *game.get_content(&Coordinate::new(1, 4)),
+            SquareContent::Tile(Tile {
+                tile_type: TileType::Two,
+                player: Player::White
+            })
+        );
+        assert_eq!(*game.get_content(&Coordinate::new(1, 5)), SquareContent::Empty);
+        assert_eq!(*game.get_content(&Coordinate::new(1, 6)), SquareContent::Empty);
+
+        // third rank
+        for file in 0..7 {
+            assert_eq!(*game.get_content(&Coordinate::new(2, file)), SquareContent::Empty);
+        }
+
+        // forth rank
+        assert_eq!(*game.get_content(&Coordinate::new(3, 0)), SquareContent::Empty);
+        assert_eq!(
+            *game.get_content(&Coordinate::new(3, 1)),
+            SquareContent::Barragoon(BarragoonFace::Blocking)
+        );
+        assert_eq!(*game.get_content(&Coordinate::new(3, 2)), SquareContent::Empty);
+        assert_eq!(*game.get_content(&Coordinate::new(3, 3)), SquareContent::Empty);
+        assert_eq!(*game.get_content(&Coordinate::new(3, 4)), SquareContent::Empty);
+        assert_eq!(
+            *game.get_content(&Coordinate::new(3, 5)),
+            SquareContent::Barragoon(BarragoonFace::Blocking)
+        );
+        assert_eq!(*game.get_content(&Coordinate::new(3, 6)), SquareContent::Empty);
+
+        // fifth rank
+        assert_eq!(
+            *game.get_content(&Coordinate::new(4, 0)),
+            SquareContent::Barragoon(BarragoonFace::Blocking)
+        );
+        assert_eq!(*game.get_content(&Coordinate::new(4, 1)), SquareContent::Empty);
+        assert_eq!(
+            *game.get_content(&Coordinate::new(4, 2)),
+            SquareContent::Barragoon(BarragoonFace::Blocking)
+        );
+        assert_eq!(*game.get_content(&Coordinate::new(4, 3)), SquareContent::Empty);
+        assert_eq!(
+            *game.get_content(&Coordinate::new(4, 4)),
+            SquareContent::Barragoon(BarragoonFace::Blocking)
+        );
+        assert_eq!(*game.get_content(&Coordinate::new(4, 5)), SquareContent::Empty);
+        assert_eq!(
+            *game.get_content(&Coordinate::new(4, 6)),
+            SquareContent::Barragoon(BarragoonFace::Blocking)
+        );
+
+        // sixth rank
+        assert_eq!(*game.get_content(&Coordinate::new(5, 0)), SquareContent::Empty);
+        assert_eq!(
+            *game.get_content(&Coordinate::new(5, 1)),
+            SquareContent::Barragoon(BarragoonFace::Blocking)
+        );
+        assert_eq!(*game.get_content(&Coordinate::new(5, 2)), SquareContent::Empty);
+        assert_eq!(*game.get_content(&Coordinate::new(5, 3)), SquareContent::Empty);
+        assert_eq!(*game.get_content(&Coordinate::new(5, 4)), SquareContent::Empty);
+        assert_eq!(
+            *game.get_content(&Coordinate::new(5, 5)),
+            SquareContent::Barragoon(BarragoonFace::Blocking)
+        );
+        assert_eq!(*game.get_content(&Coordinate::new(5, 6)), SquareContent::Empty);
+
+        // seventh rank
+        for file in 0..7 {
+            assert_eq!(*game.get_content(&Coordinate::new(6, file)), SquareContent::Empty);
+        }
+
+        // eigth rank
+        assert_eq!(*game.get_content(&Coordinate::new(7, 0)), SquareContent::Empty);
+        assert_eq!(*game.get_content(&Coordinate::new(7, 1)), SquareContent::Empty);
+        assert_eq!(
+            *game.get_content(&Coordinate::new(7, 2)),
+            SquareContent::Tile(Tile {
+                tile_type: TileType::Two,
+                player: Player::Brown
+            })
+        );
+        assert_eq!(
+            *game.get_content(&Coordinate::new(7, 3)),
+            SquareContent::Tile(Tile {
+                tile_type: TileType::Three,
+                player: Player::Brown
+            })
+        );
+        assert_eq!(
+            *game.get_content(&Coordinate::new(7, 4)),
+            SquareContent::Tile(Tile {
+                tile_type: TileType::Two,
+                player: Player::Brown
+            })
+        );
+        assert_eq!(*game.get_content(&Coordinate::new(7, 5)), SquareContent::Empty);
+        assert_eq!(*game.get_content(&Coordinate::new(7, 6)), SquareContent::Empty);
+
+        // ninth rank
+        assert_eq!(*game.get_content(&Coordinate::new(8, 0)), SquareContent::Empty);
+        assert_eq!(
+            *game.get_content(&Coordinate::new(8, 1)),
+            SquareContent::Tile(Tile {
+                tile_type: TileType::Four,
+                player: Player::Brown
+            })
+        );
+        assert_eq!(
+            *game.get_content(&Coordinate::new(8, 2)),
+            SquareContent::Tile(Tile {
+                tile_type: TileType::Three,
+                player: Player::Brown
+            })
+        );
+        assert_eq!(*game.get_content(&Coordinate::new(8, 3)), SquareContent::Empty);
+        assert_eq!(
+            *game.get_content(&Coordinate::new(8, 4)),
+            SquareContent::Tile(Tile {
+                tile_type: TileType::Three,
+                player: Player::Brown
+            })
+        );
+        assert_eq!(
+            *game.get_content(&Coordinate::new(8, 5)),
+            SquareContent::Tile(Tile {
+                tile_type: TileType::Four,
+                player: Player::Brown
+            })
+        );
+        assert_eq!(*game.get_content(&Coordinate::new(8, 6)), SquareContent::Empty);
+    }
+
+    #[test]
+    fn fen_chars_map_to_player_names() {
+        for tile_type in TileType::iter() {
+            let white = Tile {
+                tile_type,
+                player: Player::White,
+            };
+            let brown = Tile {
+                tile_type,
+                player: Player::Brown,
+            };
+
+            assert!(white.as_fen_char().is_ascii_uppercase());
+            assert!(brown.as_fen_char().is_ascii_lowercase());
+            assert_eq!(white.as_fen_char().to_ascii_lowercase(), brown.as_fen_char());
+        }
+    }
+
+    #[test]
+    fn one_way_glyph_is_flipped_for_brown() {
+        let north = SquareContent::Barragoon(BarragoonFace::OneWay {
+            direction: Direction::North,
+        });
+
+        assert_eq!(north.as_fen_char_oriented(Player::White), '^');
+        assert_eq!(north.as_fen_char_oriented(Player::Brown), 'Y');
+    }
+
+    #[test]
+    fn orientation_keeps_symmetric_faces_and_turn_handedness() {
+        let straight = BarragoonFace::Straight { alignment: Ba::Vertical };
+        let turn = BarragoonFace::OneWayTurnLeft {
+            direction: Direction::East,
+        };
+
+        assert_eq!(straight.oriented_for(Player::Brown), straight);
+        assert_eq!(BarragoonFace::ForceTurn.oriented_for(Player::Brown), BarragoonFace::ForceTurn);
+        assert_eq!(turn.oriented_for(Player::White), turn);
+        assert_eq!(
+            turn.oriented_for(Player::Brown),
+            BarragoonFace::OneWayTurnLeft {
+                direction: Direction::West
+            }
+        );
+    }
+
+    #[test]
+    fn file_squares_match_content() {
+        let game = Game::new();
+        let file: Vec<(Coordinate, &SquareContent)> = game.file_squares(1).collect();
+
+        assert_eq!(file.len(), BOARD_HEIGHT as usize);
+        for (rank, (coordinate, content)) in (0..BOARD_HEIGHT).zip(file) {
+            assert_eq!(coordinate, Coordinate::new(rank, 1));
+            assert_eq!(content, game.get_content(&coordinate));
+        }
+    }
+
+    #[test]
+    fn rank_squares_match_content() {
+        let game = Game::new();
+        let rank: Vec<(Coordinate, &SquareContent)> = game.rank_squares(4).collect();
+
+        assert_eq!(rank.len(), BOARD_WIDTH as usize);
+        for (file, (coordinate, content)) in (0..BOARD_WIDTH).zip(rank) {
+            assert_eq!(coordinate, Coordinate::new(4, file));
+            assert_eq!(content, game.get_content(&coordinate));
+        }
+    }
+
+    #[test]
+    fn start_position_empty_and_occupied_squares() {
+        let game = Game::new();
+
+        for occupied in [
+            Coordinate::new(0, 1),
+            Coordinate::new(3, 1),
+            Coordinate::new(4, 0),
+            Coordinate::new(8, 5),
+        ] {
+            assert!(game.is_occupied(&occupied));
+            assert!(!game.is_empty(&occupied));
+        }
+
+        for empty in [
+            Coordinate::new(0, 0),
+            Coordinate::new(2, 3),
+            Coordinate::new(4, 3),
+            Coordinate::new(8, 6),
+        ] {
+            assert!(game.is_empty(&empty));
+            assert!(!game.is_occupied(&empty));
+        }
+    }
+
+    #[test]
+    fn start_position_has_maximal_phase() {
+        assert_eq!(Game::new().phase(), u8::MAX);
+    }
+
+    #[test]
+    fn sparse_position_has_low_phase() {
+        let game = Game::from_fen("3z3/7/7/7/7/7/7/7/3Z3").expect("FEN is valid.");
+
+        assert!(game.phase() < u8::MAX / 4);
+        assert_eq!(Game::empty().phase(), 0);
+    }
+
+    #[test]
+    fn game_from_squares_matches_poked_board() {
+        let white_two = SquareContent::Tile(Tile {
+            tile_type: TileType::Two,
+            player: Player::White,
+        });
+        let brown_four = SquareContent::Tile(Tile {
+            tile_type: TileType::Four,
+            player: Player::Brown,
+        });
+
+        let mut poked = Game::empty();
+        poked.board[2][3] = white_two;
+        poked.board[6][5] = brown_four;
+
+        let built = Game::from_squares([(Coordinate::new(2, 3), white_two), (Coordinate::new(6, 5), brown_four)]);
+        let collected: Game = [(Coordinate::new(2, 3), white_two), (Coordinate::new(6, 5), brown_four)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(built.board, poked.board);
+        assert_eq!(collected.board, poked.board);
+    }
+
+    #[test]
+    fn pretty_fen_has_a_line_per_rank() {
+        let pretty = Game::new().pretty_fen();
+
+        assert_eq!(pretty.lines().count(), BOARD_HEIGHT as usize);
+        assert!(pretty.lines().all(|line| line.chars().count() == BOARD_WIDTH as usize));
+        assert_eq!(pretty.lines().next(), Some(".vd.dv."));
+        assert_eq!(pretty.lines().nth(4), Some("x.x.x.x"));
+    }
+
+    #[test]
+    fn fen_dialects_swap_tile_letters() {
+        let swapped_case = FenDialect::new(['z', 'd', 'v'], ['Z', 'D', 'V']).expect("Letters are distinct tile letters.");
+        let game = Game::from_fen("1vd1dv1/2zdz2/7/1x3x1/x1+1-1x/1S3n1/7/2ZDZ2/1VD1DV1").expect("FEN is valid.");
+        let swapped_fen = game.board_fen_with(&swapped_case);
+
+        assert_eq!(swapped_fen, "1VD1DV1/2ZDZ2/7/1x3x1/x1+1-1x/1S3n1/7/2zdz2/1vd1dv1");
+        assert_eq!(Game::from_fen_with(&swapped_fen, &swapped_case).expect("FEN is valid."), game);
+        assert_eq!(game.board_fen_with(&FenDialect::default()), game.board_fen());
+
+        let letters = FenDialect::new(['T', 'H', 'F'], ['t', 'h', 'f']).expect("Letters are distinct tile letters.");
+        assert!(matches!(
+            Game::from_fen_with("Z6/7/7/7/7/7/7/7/7", &letters),
+            Err(FenError::InvalidChar { char_index: 0 })
+        ));
+        assert_eq!(
+            Game::from_fen_with("T5f/7/7/7/7/7/7/7/7", &letters)
+                .expect("FEN is valid.")
+                .board_fen(),
+            "Z5v/7/7/7/7/7/7/7/7"
+        );
+    }
+
+    #[test]
+    fn fen_dialects_reject_clashing_letters() {
+        assert_eq!(FenDialect::new(['Z', 'D', 'V'], ['z', 'd', 'v']), Some(FenDialect::default()));
+        for clash in ['S', 'N', 'E', 'W', 's', 'n', 'e', 'w', 'x', 'Y', '+', '<', '3', '/', 'd'] {
+            assert_eq!(
+                FenDialect::new(['Z', 'D', 'V'], ['z', 'd', clash]),
+                None,
+                "{clash} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn board_fen_of_start_position() {
+        assert_eq!(Game::new().board_fen(), INITIAL_FEN_STRING);
+        assert_eq!(Game::empty().board_fen(), EMPTY_FEN_STRING);
+    }
+
+    #[test]
+    fn mirrored_positions_share_canonical_hash() {
+        let game = Game::from_fen("7/7/7/7/1>4Z/7/7/7/2n4").expect("FEN is valid.");
+        let mirrored = game.mirror_files();
+
+        assert_eq!(mirrored, Game::from_fen("7/7/7/7/Z4<1/7/7/7/4N2").expect("FEN is valid."));
+        assert_ne!(game.position_hash(), mirrored.position_hash());
+        assert_eq!(game.canonical_hash(), mirrored.canonical_hash());
+        assert_eq!(mirrored.mirror_files(), game);
+    }
+
+    #[test]
+    fn start_position_looks_playable() {
+        assert!(Game::new().looks_playable());
+    }
+
+    #[test]
+    fn overfull_or_deserted_positions_do_not_look_playable() {
+        let overfull = Game::from_fen("1vd1dv1/2zdz2/7/1x3x1/x1x1x1x/1x3x1/7/2ZDZZ1/1VD1DV1").expect("FEN is valid.");
+
+        assert!(!overfull.looks_playable());
+        assert!(!Game::empty().looks_playable());
+        assert!(!Game::from_fen("7/7/7/7/7/7/7/7/3Z3").expect("FEN is valid.").looks_playable());
+    }
+
+    #[test]
+    fn place_onto_empty_square() {
+        let mut game = Game::empty();
+
+        assert_eq!(
+            game.place(&Coordinate::new(3, 2), SquareContent::Barragoon(BarragoonFace::ForceTurn)),
+            Ok(())
+        );
+        assert_eq!(
+            *game.get_content(&Coordinate::new(3, 2)),
+            SquareContent::Barragoon(BarragoonFace::ForceTurn)
+        );
+    }
+
+    #[test]
+    fn place_rejects_occupied_and_out_of_range_squares() {
+        let mut game = Game::new();
+        let barragoon = SquareContent::Barragoon(BarragoonFace::Blocking);
+
+        assert_eq!(game.place(&Coordinate::new(0, 1), barragoon), Err(PlacementError::Occupied));
+        assert_eq!(game.place(&Coordinate::new(9, 0), barragoon), Err(PlacementError::OutOfBounds));
+        assert_eq!(game.place(&Coordinate::new(0, 7), barragoon), Err(PlacementError::OutOfBounds));
+        assert_eq!(game, Game::new());
+    }
+
+    #[test]
+    fn clear_empties_square() {
+        let mut game = Game::new();
+
+        assert_eq!(game.clear(&Coordinate::new(0, 1)), Ok(()));
+        assert!(game.is_empty(&Coordinate::new(0, 1)));
+        assert_eq!(game.clear(&Coordinate::new(9, 9)), Err(PlacementError::OutOfBounds));
+    }
+
+    #[test]
+    fn start_position_satisfies_invariants() {
+        assert_eq!(Game::new().check_invariants(), Ok(()));
+        assert_eq!(Game::empty().check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn too_many_tiles_violate_invariants() {
+        let mut game = Game::new();
+        game.board[4][1] = SquareContent::Tile(Tile {
+            tile_type: TileType::Four,
+            player: Player::White,
+        });
+
+        assert!(game.check_invariants().is_err());
+    }
+
+    #[test]
+    fn too_many_barragoons_violate_invariants() {
+        let game = Game::from_fen("xxxxxxx/xxxxxxx/xxxxxxx/xxxxxxx/xxxxxxx/7/7/7/7").expect("FEN is valid.");
+
+        assert!(game.check_invariants().is_err());
+    }
+
+    #[test]
+    fn diff_of_identical_games_is_empty() {
+        assert!(Game::new().diff(&Game::new()).is_empty());
+    }
+
+    #[test]
+    fn diff_after_single_move_lists_affected_squares() {
+        let before = Game::new();
+        let tile_move = before.valid_moves()[0];
+        let mut after = before;
+        after.make_move(&tile_move);
+
+        let Move::Straight { moving_tile, start, stop } = tile_move else {
+            panic!("First move of the start position should be a straight move.");
+        };
+
+        assert_eq!(
+            before.diff(&after),
+            vec![
+                (start, SquareContent::Tile(moving_tile), SquareContent::Empty),
+                (stop, SquareContent::Empty, SquareContent::Tile(moving_tile))
+            ]
+        );
+    }
+
+    #[test]
+    fn null_move_passes_turn_and_keeps_board() {
+        let game = Game::new();
+        let passed = game.null_move().expect("Start position has moves.");
+
+        assert_eq!(passed.current_player, Player::Brown);
+        assert_eq!(passed.board, game.board);
+        assert_eq!(passed.valid_moves().len(), 28);
+        for brown_move in passed.valid_moves() {
+            let Move::Straight { moving_tile, .. } = brown_move else {
+                panic!("Start position only has straight moves.");
+            };
+            assert_eq!(moving_tile.player, Player::Brown);
+        }
+    }
+
+    #[test]
+    fn null_move_requires_moves() {
+        assert!(Game::empty().null_move().is_none());
+    }
+
+    #[test]
+    fn straight_faces_traversal_tables() {
+        assert_eq!(
+            BarragoonFace::Straight { alignment: Ba::Horizontal }.traversal_table(),
+            vec![(Direction::West, Direction::West), (Direction::East, Direction::East)]
+        );
+        assert_eq!(
+            BarragoonFace::Straight { alignment: Ba::Vertical }.traversal_table(),
+            vec![(Direction::North, Direction::North), (Direction::South, Direction::South)]
+        );
+        assert_eq!(
+            BarragoonFace::OneWay {
+                direction: Direction::East
+            }
+            .traversal_table(),
+            vec![(Direction::East, Direction::East)]
+        );
+        assert!(BarragoonFace::Blocking.traversal_table().is_empty());
+    }
+
+    #[test]
+    fn turning_faces_traversal_tables() {
+        assert_eq!(BarragoonFace::ForceTurn.traversal_table().len(), 8);
+        assert_eq!(
+            BarragoonFace::OneWayTurnLeft {
+                direction: Direction::West
+            }
+            .traversal_table(),
+            vec![(Direction::North, Direction::West)]
+        );
+        assert_eq!(
+            BarragoonFace::OneWayTurnRight {
+                direction: Direction::West
+            }
+            .traversal_table(),
+            vec![(Direction::South, Direction::West)]
+        );
+    }
+
+    #[test]
+    fn tiles_pass_straight_through_aligned_barragoons() {
+        let reaches = |fen: &str, target: Coordinate| {
+            let game = Game::from_fen(fen).expect("FEN is valid.");
+            game.valid_moves().iter().any(|tile_move| tile_move.stop() == target)
+        };
+
+        // through a horizontal barragoon ...
+        assert!(reaches("7/7/7/7/7/7/7/7/Z-5", Coordinate::new(0, 2)));
+        assert!(!reaches("7/7/7/7/7/7/7/7/Z|5", Coordinate::new(0, 2)));
+        // ... and along a one way barragoon, but not against it
+        assert!(reaches("7/7/7/7/7/7/7/7/2Z>3", Coordinate::new(0, 4)));
+        assert!(reaches("7/7/7/7/7/7/7/7/4<1Z", Coordinate::new(0, 4)));
+        assert!(!reaches("7/7/7/7/7/7/7/7/4>1Z", Coordinate::new(0, 4)));
+    }
+
+    #[test]
+    fn force_turn_can_be_traversed_by_turning() {
+        for enter_dir in Direction::iter() {
+            assert!(BarragoonFace::ForceTurn.can_be_traversed(enter_dir, enter_dir.turn_left()));
+            assert!(BarragoonFace::ForceTurn.can_be_traversed(enter_dir, enter_dir.turn_right()));
+        }
+    }
+
+    #[test]
+    fn force_turn_cannot_be_traversed_straight_or_backwards() {
+        for enter_dir in Direction::iter() {
+            assert!(!BarragoonFace::ForceTurn.can_be_traversed(enter_dir, enter_dir));
+            assert!(!BarragoonFace::ForceTurn.can_be_traversed(enter_dir, enter_dir.opposite()));
+        }
+    }
+
+    #[test]
+    fn force_turn_is_only_a_pivot() {
+        let game = Game::from_fen("7/7/7/7/7/7/7/+6/D6").expect("FEN is valid.");
+        let force_turn = Coordinate::new(1, 0);
+        let moves = game.valid_moves();
+
+        // turning on the barragoon is fine ...
+        assert!(moves
+            .iter()
+            .any(|tile_move| matches!(tile_move, Move::Straight { stop, .. } if *stop == Coordinate::new(1, 2))));
+        // ... passing straight through it is not ...
+        assert!(moves
+            .iter()
+            .all(|tile_move| !matches!(tile_move, Move::Straight { stop, .. } if stop.file == 0)));
+
+        // ... and a tile that cannot capture it cannot end its move there either.
+        let game = Game::from_fen("7/7/7/7/7/7/7/+6/1Z5").expect("FEN is valid.");
+        assert!(game.valid_moves().iter().all(|tile_move| match tile_move {
+            Move::Straight { stop, .. } | Move::BarragoonCapture { stop, .. } => *stop != force_turn,
+            Move::TileCapture { .. } => true,
+        }));
+    }
+
+    #[test]
+    fn start_position_barragoons() {
+        let expected: Vec<(Coordinate, BarragoonFace)> = [(3, 1), (3, 5), (4, 0), (4, 2), (4, 4), (4, 6), (5, 1), (5, 5)]
+            .into_iter()
+            .map(|(rank, file)| (Coordinate::new(rank, file), BarragoonFace::Blocking))
+            .collect();
+
+        assert_eq!(Game::new().barragoons(), expected);
+        assert!(Game::empty().barragoons().is_empty());
+    }
+
+    #[test]
+    fn three_can_capture_force_turn() {
+        let game = Game::from_fen("7/7/7/7/7/7/7/7/D2+3").expect("FEN is valid.");
+
+        assert_eq!(
+            game.capturable_barragoons(),
+            vec![(Coordinate::new(0, 3), BarragoonFace::ForceTurn)]
+        );
+    }
+
+    #[test]
+    fn two_cannot_capture_force_turn() {
+        let game = Game::from_fen("7/7/7/7/7/7/7/7/1Z1+3").expect("FEN is valid.");
+
+        assert!(game.capturable_barragoons().is_empty());
+    }
+
+    #[test]
+    fn moves_round_trip_through_u32() {
+        let mut game = Game::new();
+        for _ in 0..6 {
+            for player in [Player::White, Player::Brown] {
+                let mut position = game;
+                position.set_current_player(player);
+                for tile_move in position.valid_moves() {
+                    assert_eq!(Move::from_u32(tile_move.to_u32()), Some(tile_move));
+                }
+            }
+            let next_move = *game.valid_moves().last().expect("Game has moves.");
+            game.make_move(&next_move);
+        }
+    }
+
+    #[test]
+    fn invalid_codes_are_rejected() {
+        assert_eq!(Move::from_u32(3), None);
+        assert_eq!(Move::from_u32(63 << 2), None);
+        assert_eq!(Move::from_u32(1 << 20), None);
+        assert_eq!(Move::from_u32(3 << 14), None);
+    }
+
+    #[test]
+    fn fen_sets_side_to_move() {
+        let board = "1vd1dv1/2zdz2/7/1x3x1/x1x1x1x/1x3x1/7/2ZDZ2/1VD1DV1";
+
+        assert_eq!(
+            Game::from_fen(&format!("{board} w")).expect("FEN is valid.").current_player(),
+            Player::White
+        );
+        assert_eq!(
+            Game::from_fen(&format!("{board} b")).expect("FEN is valid.").current_player(),
+            Player::Brown
+        );
+        assert_eq!(Game::from_fen(board).expect("FEN is valid.").current_player(), Player::White);
+        assert_eq!(
+            Game::from_fen(&format!("{board} b")).expect("FEN is valid.").board,
+            Game::new().board
+        );
+    }
+
+    #[test]
+    fn fen_rejects_unknown_side_to_move() {
+        let board = "7/7/7/7/7/7/7/7/7";
+
+        assert!(matches!(
+            Game::from_fen(&format!("{board} x")),
+            Err(FenError::InvalidPlayer { char_index: 18 })
+        ));
+        assert!(matches!(
+            Game::from_fen(&format!("{board}  white")),
+            Err(FenError::InvalidPlayer { char_index: 19 })
+        ));
+    }
+
+    #[test]
+    fn lenient_fen_pads_short_ranks() {
+        let fen = "Z/7/7/7/7/7/7/7/2z";
+
+        assert!(matches!(Game::from_fen(fen), Err(FenError::UnderfullLine { char_index: 1 })));
+        let game = Game::from_fen_lenient(fen).expect("Short ranks are padded.");
+        assert_eq!(game.as_fen(), "Z6/7/7/7/7/7/7/7/2z4 w");
+    }
+
+    #[test]
+    fn players_head_for_each_others_home_rank() {
+        for player in [Player::White, Player::Brown] {
+            assert_ne!(player.home_rank(), player.goal_rank());
+            assert_eq!(player.goal_rank(), player.opponent().home_rank());
+        }
+
+        let start = Game::new();
+        for player in [Player::White, Player::Brown] {
+            assert!(start
+                .rank_squares(player.home_rank())
+                .any(|(_, content)| matches!(content, SC::Tile(tile) if tile.player == player)));
+        }
+    }
+
+    #[test]
+    fn goal_moves_reach_the_goal_rank() {
+        let mut game = Game::from_fen("7/3Z3/7/7/7/7/7/3z3/7").expect("FEN is valid.");
+        let goal_moves = game.goal_moves();
+
+        assert!(!goal_moves.is_empty());
+        assert!(goal_moves.iter().all(|tile_move| tile_move.stop().rank == BOARD_HEIGHT - 1));
+        assert!(goal_moves.len() < game.valid_moves().len());
+
+        game.set_current_player(Player::Brown);
+        assert!(game.goal_moves().iter().all(|tile_move| tile_move.stop().rank == 0));
+        assert!(!game.goal_moves().is_empty());
+        assert!(Game::new().goal_moves().is_empty());
+    }
+
+    #[test]
+    fn goal_threats_name_the_threatening_tiles() {
+        let game = Game::from_fen("7/3Z3/7/7/7/7/7/3z3/V6").expect("FEN is valid.");
+
+        assert_eq!(game.goal_threats(Player::White), vec![Coordinate::new(7, 3)]);
+        assert_eq!(game.goal_threats(Player::Brown), vec![Coordinate::new(1, 3)]);
+        assert!(Game::new().goal_threats(Player::White).is_empty());
+    }
+
+    #[test]
+    fn winning_threats_approach_the_goal_rank() {
+        let game = Game::from_fen("3z3/7/7/3Z3/7/7/7/7/7 w").expect("FEN is valid.");
+        let start = Coordinate::new(5, 3);
+        let threats = game.winning_threats();
+
+        assert!(threats.contains(&Move::Straight {
+            moving_tile: Tile {
+                tile_type: TileType::Two,
+                player: Player::White
+            },
+            start,
+            stop: Coordinate::new(7, 3)
+        }));
+        assert!(threats.iter().all(|tile_move| tile_move.stop().rank >= 6));
+        assert!(threats.len() < game.valid_moves().len());
+    }
+
+    #[test]
+    fn safe_moves_keep_the_lane_to_the_goal_rank_closed() {
+        let game = Game::from_fen("7/7/7/7/V6/7/3z3/3D3/7 w").expect("FEN is valid.");
+        let blocker = Coordinate::new(1, 3);
+        let opening_move = *game
+            .valid_moves()
+            .iter()
+            .find(|tile_move| tile_move.start() == blocker && tile_move.stop() == Coordinate::new(1, 0))
+            .expect("D can step aside.");
+        let safe_moves = game.safe_moves();
+
+        assert!(game.goal_threats(Player::Brown).is_empty());
+        assert!(!safe_moves.contains(&opening_move));
+        assert!(!safe_moves.is_empty());
+        assert!(safe_moves.iter().all(|tile_move| tile_move.start() != blocker));
+    }
+
+    #[test]
+    fn moves_capturing_lists_every_attacker() {
+        let game = Game::from_fen("7/7/7/7/7/7/3v3/2Z1Z2/7").expect("FEN is valid.");
+        let victim = Coordinate::new(2, 3);
+        let captures = game.moves_capturing(&victim);
+        let attackers: Vec<Coordinate> = captures.iter().map(Move::start).collect();
+
+        assert_eq!(attackers, vec![Coordinate::new(1, 2), Coordinate::new(1, 4)]);
+        assert!(captures.iter().all(|capture| capture.stop() == victim));
+        assert!(game.moves_capturing(&Coordinate::new(1, 2)).is_empty());
+    }
+
+    #[test]
+    fn opponent_captures_after_finds_the_hung_tile() {
+        let game = Game::from_fen("7/7/7/7/3z3/7/7/7/3Z3").expect("FEN is valid.");
+        let hanging_move = *game
+            .valid_moves()
+            .iter()
+            .find(|tile_move| tile_move.stop() == Coordinate::new(2, 3))
+            .expect("Z can move to d3.");
+        let replies = game.opponent_captures_after(&hanging_move);
+
+        assert!(replies.iter().any(|reply| reply.kind() == MoveKind::TileCapture
+            && reply.start() == Coordinate::new(4, 3)
+            && reply.stop() == Coordinate::new(2, 3)));
+    }
+
+    #[test]
+    fn defensive_moves_save_the_hanging_tile() {
+        let game = Game::from_fen("7/7/7/7/7/7/1z5/7/1Z5").expect("FEN is valid.");
+        let mut brown_to_move = game;
+        brown_to_move.set_current_player(Player::Brown);
+        let hanging = Coordinate::new(2, 1);
+        let defences = brown_to_move.defensive_moves(&hanging);
+
+        let escape = Coordinate::new(4, 1);
+
+        assert!(defences.iter().any(|defence| defence.stop() == escape));
+        for tile_move in brown_to_move.valid_moves() {
+            let mut after = brown_to_move;
+            after.make_move(&tile_move);
+            let captured = after.valid_moves().iter().any(|reply| reply.stop() == tile_move.stop());
+            assert_eq!(defences.contains(&tile_move), !captured, "{tile_move:?}");
+        }
+        assert!(defences.len() < brown_to_move.valid_moves().len());
+    }
+
+    #[test]
+    fn capture_victim_names_the_captured_content() {
+        let game = Game::from_fen("7/7/7/7/7/7/1z5/7/1Z5").expect("FEN is valid.");
+        let moves = game.valid_moves();
+        let tile_capture = moves.iter().find(|m| m.kind() == MoveKind::TileCapture).expect("Z can capture z.");
+        let straight = moves.iter().find(|m| m.kind() == MoveKind::Quiet).expect("Z has a quiet move.");
+
+        assert_eq!(game.capture_victim(tile_capture), SC::from_fen_char('z'));
+        assert_eq!(game.capture_victim(straight), None);
+    }
+
+    #[test]
+    fn summary_digests_the_position() {
+        assert_eq!(Game::new().summary(), format!("{INITIAL_FEN_STRING} w White moves 28 material +0"));
+
+        let game = Game::from_fen("7/7/7/7/7/7/7/7/V5z").expect("FEN is valid.");
+        assert!(game.summary().ends_with("material +2"));
+    }
+
+    #[test]
+    fn mobility_balance_compares_move_counts() {
+        assert_eq!(Game::new().mobility_balance(), 0);
+
+        let mut game = Game::from_fen("7/7/7/7/3V3/7/7/7/Z5z").expect("FEN is valid.");
+        let white_balance = game.mobility_balance();
+        game.set_current_player(Player::Brown);
+
+        assert!(white_balance > 0);
+        assert_eq!(game.mobility_balance(), -white_balance);
+    }
+
+    #[test]
+    fn tile_surrounded_by_blocking_barragoons_is_trapped() {
+        let game = Game::from_fen("7/7/7/3x3/2xZx2/3x3/7/7/Z6").expect("FEN is valid.");
+
+        assert!(game.is_trapped(&Coordinate::new(4, 3)));
+        assert!(!game.is_trapped(&Coordinate::new(0, 0)));
+        assert!(!game.is_trapped(&Coordinate::new(5, 3)));
+        assert!(!Game::new().is_trapped(&Coordinate::new(8, 1)));
+    }
+
+    #[test]
+    fn valid_moves_come_in_a_fixed_order() {
+        let game = Game::from_fen("7/7/7/7/7/7/1x5/7/1Z1D3").expect("FEN is valid.");
+        let ranges: Vec<String> = game
+            .valid_moves()
+            .iter()
+            .map(|tile_move| navigation::format_square_range(tile_move.start(), tile_move.stop()))
+            .collect();
+
+        assert_eq!(
+            ranges,
+            [
+                "b1-b3", "b1-a2", "b1-c2", "b1-b2", "b1-a1", "b1-c1", "d1-d4", "d1-b2", "d1-f2", "d1-c3", "d1-e3", "d1-g1", "d1-d3",
+                "d1-c2", "d1-e2", "d1-f1"
+            ]
+        );
+        assert_eq!(game.valid_moves()[0].kind(), MoveKind::BarragoonCapture);
+
+        for _ in 0..3 {
+            assert_eq!(Game::new().valid_moves(), Game::new().valid_moves());
+        }
+    }
+
+    #[test]
+    fn generate_moves_into_refills_the_buffer() {
+        let mut buf = vec![];
+        let mut game = Game::new();
+        for _ in 0..3 {
+            game.generate_moves_into(&mut buf);
+            assert_eq!(buf, game.valid_moves());
+            game.make_move(&buf[0]);
+        }
+    }
+
+    #[test]
+    fn disabled_barragoons_do_not_block() {
+        let mut game = Game::from_fen("7/7/7/7/7/7/3x3/3x3/3D3").expect("FEN is valid.");
+        let through = Coordinate::new(3, 3);
+        assert!(!game.valid_moves().iter().any(|tile_move| tile_move.stop() == through));
+
+        game.set_rules(Rules {
+            barragoons_enabled: false,
+            ..Rules::default()
+        });
+        let moves = game.valid_moves();
+
+        assert!(moves.iter().any(|tile_move| tile_move.stop() == through));
+        assert!(moves.iter().all(|tile_move| tile_move.kind() == MoveKind::Quiet));
+        assert!(moves.iter().all(|tile_move| game.is_legal(tile_move)));
+    }
+
+    #[test]
+    fn disabled_barragoons_stay_on_the_board() {
+        let mut game = Game::new();
+        game.set_rules(Rules {
+            barragoons_enabled: false,
+            ..Rules::default()
+        });
+        let barragoon_count = game.barragoons().len();
+
+        for first_move in game.valid_moves() {
+            let mut after_first = game;
+            after_first.make_move(&first_move);
+            assert_eq!(after_first.barragoons().len(), barragoon_count, "{first_move} removed a barragoon");
+
+            for second_move in after_first.valid_moves() {
+                let mut after_second = after_first;
+                after_second.make_move(&second_move);
+                assert_eq!(
+                    after_second.barragoons().len(),
+                    barragoon_count,
+                    "{second_move} removed a barragoon"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn path_squares_cross_traversable_barragoons() {
+        let game = Game::from_fen("7/7/7/7/7/7/7/3|3/3V3").expect("FEN is valid.");
+        let through = *game
+            .valid_moves()
+            .iter()
+            .find(|tile_move| tile_move.stop() == Coordinate::new(4, 3))
+            .expect("V can move across the barragoon.");
+
+        assert_eq!(
+            game.path_squares(&through),
+            [1, 2, 3, 4].map(|rank| Coordinate::new(rank, 3)).to_vec()
+        );
+        assert!(game
+            .path_squares(&Move::BarragoonCapture {
+                start: Coordinate::new(0, 3),
+                stop: Coordinate::new(1, 3)
+            })
+            .is_empty());
+    }
+
+    #[test]
+    fn has_capture_detects_captures() {
+        let game = Game::from_fen("7/7/7/7/7/7/1z5/7/1Z5").expect("FEN is valid.");
+
+        assert!(game.has_capture());
+        assert!(!Game::new().has_capture());
+    }
+
+    #[test]
+    fn mandatory_capture_drops_quiet_moves() {
+        let mut game = Game::from_fen("7/7/7/7/7/7/1z5/7/1Z5").expect("FEN is valid.");
+        let all_moves = game.valid_moves();
+
+        game.set_rules(Rules {
+            mandatory_capture: true,
+            ..Rules::default()
+        });
+        let forced_moves = game.valid_moves();
+
+        assert!(forced_moves.len() < all_moves.len());
+        assert!(!forced_moves.is_empty());
+        assert!(forced_moves.iter().all(|tile_move| tile_move.kind() == MoveKind::TileCapture));
+        assert!(all_moves
+            .iter()
+            .all(|tile_move| forced_moves.contains(tile_move) == game.is_legal(tile_move)));
+    }
+
+    #[test]
+    fn mandatory_capture_keeps_quiet_moves_without_captures() {
+        let mut game = Game::new();
+        game.set_rules(Rules {
+            mandatory_capture: true,
+            ..Rules::default()
+        });
+
+        assert_eq!(game.valid_moves(), Game::new().valid_moves());
+    }
+
+    #[test]
+    fn moves_know_their_kind() {
+        let tile = Tile {
+            tile_type: TileType::Two,
+            player: Player::White,
+        };
+        let victim = Tile {
+            tile_type: TileType::Four,
+            player: Player::Brown,
+        };
+        let (start, stop) = (Coordinate::new(0, 0), Coordinate::new(2, 0));
+
+        assert_eq!(
+            Move::Straight {
+                moving_tile: tile,
+                start,
+                stop
+            }
+            .kind(),
+            MoveKind::Quiet
+        );
+        assert_eq!(
+            Move::TileCapture {
+                from: (tile, start),
+                to: (victim, stop)
+            }
+            .kind(),
+            MoveKind::TileCapture
+        );
+        assert_eq!(Move::BarragoonCapture { start, stop }.kind(), MoveKind::BarragoonCapture);
+    }
+
+    #[test]
+    fn moves_list_their_squares() {
+        let tile = Tile {
+            tile_type: TileType::Two,
+            player: Player::White,
+        };
+        let straight = Move::Straight {
+            moving_tile: tile,
+            start: Coordinate::new(1, 2),
+            stop: Coordinate::new(3, 2),
+        };
+        let barragoon_capture = Move::BarragoonCapture {
+            start: Coordinate::new(1, 2),
+            stop: Coordinate::new(2, 3),
+        };
+
+        assert_eq!(straight.squares(), vec![Coordinate::new(1, 2), Coordinate::new(3, 2)]);
+        assert_eq!(barragoon_capture.squares(), vec![Coordinate::new(1, 2), Coordinate::new(2, 3)]);
+    }
+
+    #[test]
+    fn territory_marks_exclusive_reach() {
+        let game = Game::from_fen("7/7/7/7/3z3/7/7/7/3Z3").expect("FEN is valid.");
+        let territory = game.territory();
+
+        // only the white Two reaches c1 ...
+        assert_eq!(territory[0][2], 1);
+        // ... only the brown Two reaches d6 ...
+        assert_eq!(territory[5][3], -1);
+        // ... both reach d3 and nobody reaches a9.
+        assert_eq!(territory[2][3], 0);
+        assert!(game.reachable_squares(Player::White).contains(&Coordinate::new(2, 3)));
+        assert!(game.reachable_squares(Player::Brown).contains(&Coordinate::new(2, 3)));
+        assert_eq!(territory[8][0], 0);
+    }
+
+    #[test]
+    fn built_barragoon_capture_is_valid() {
+        let game = Game::from_fen("7/7/7/7/7/7/7/7/D2+3").expect("FEN is valid.");
+        let capture = game
+            .build_barragoon_capture(&Coordinate::new(0, 0), &Coordinate::new(0, 3))
+            .expect("Board holds a tile and a barragoon.");
+
+        assert!(game.valid_moves().contains(&capture));
+    }
+
+    #[test]
+    fn barragoon_capture_needs_own_tile_and_barragoon() {
+        let game = Game::from_fen("7/7/7/7/7/7/7/7/D1d+3").expect("FEN is valid.");
+
+        assert_eq!(game.build_barragoon_capture(&Coordinate::new(0, 0), &Coordinate::new(0, 2)), None);
+        assert_eq!(game.build_barragoon_capture(&Coordinate::new(0, 2), &Coordinate::new(0, 3)), None);
+        assert_eq!(game.build_barragoon_capture(&Coordinate::new(0, 1), &Coordinate::new(0, 3)), None);
+        assert_eq!(game.build_barragoon_capture(&Coordinate::new(0, 0), &Coordinate::new(0, 9)), None);
+    }
+
+    #[test]
+    fn walled_in_force_turn_is_immovable() {
+        let game = Game::from_fen("7/7/7/2xxx2/2x+x2/2xxx2/7/7/D5d").expect("FEN is valid.");
+        let immovable = game.immovable_barragoons();
+
+        assert!(immovable.contains(&Coordinate::new(4, 3)));
+        assert!(!game
+            .capturable_barragoons()
+            .iter()
+            .any(|(coordinate, _)| *coordinate == Coordinate::new(4, 3)));
+    }
+
+    #[test]
+    fn reachable_barragoons_are_not_immovable() {
+        let game = Game::from_fen("7/7/7/7/7/7/7/7/D2+3").expect("FEN is valid.");
+
+        assert!(game.immovable_barragoons().is_empty());
+    }
+
+    #[test]
+    fn initial_gamestate_allowed_moves() {
+        let moves = Game::new().valid_moves();
+        assert_eq!(moves.len(), 28);
+    }
+
+    #[test]
+    fn crowded_board_moves_match_formula() {
+        // rank 1 holds white Twos, rank 2 is empty, rank 3 is a wall of blocking barragoons and the rest is packed with
+        // brown tiles. Every Two can step forward, jump onto the barragoon in front of it, and bend sideways on rank 2.
+        let game = Game::from_fen("vdzvdzv/zdvzdvz/vzdvzdv/dvzdvzd/zzzzzzz/vvvvvvv/xxxxxxx/7/ZZZZZZZ").expect("FEN is valid.");
+        let moves = game.valid_moves();
+
+        let expected: usize = (0..BOARD_WIDTH)
+            .map(|file| 2 + usize::from(file > 0) + usize::from(file < BOARD_WIDTH - 1))
+            .sum();
+        assert_eq!(moves.len(), expected);
+        assert_eq!(
+            moves
+                .iter()
+                .filter(|tile_move| matches!(tile_move, Move::BarragoonCapture { .. }))
+                .count(),
+            BOARD_WIDTH as usize
+        );
+    }
+
+    #[test]
+    fn full_board_of_barragoons_has_no_moves() {
+        let game = Game::from_fen("xxxxxxx/xxxxxxx/xxxxxxx/xxxxxxx/xxxVxxx/xxxxxxx/xxxxxxx/xxxxxxx/xxxxxxx").expect("FEN is valid.");
+
+        assert!(game.valid_moves().is_empty());
+    }
+
+    #[test]
+    fn straight_move_legality() {
+        let game = Game::from_fen("7/7/7/7/7/7/7/2z4/2Z4").expect("FEN is valid.");
+        let white_two = Tile {
+            tile_type: TileType::Two,
+            player: Player::White,
+        };
+        let straight = |stop| Move::Straight {
+            moving_tile: white_two,
+            start: Coordinate::new(0, 2),
+            stop,
+        };
+
+        // sideways is free, bending around the brown tile as well ...
+        assert!(game.is_legal(&straight(Coordinate::new(0, 4))));
+        assert!(game.is_legal(&straight(Coordinate::new(1, 3))));
+        // ... but the brown tile blocks going straight ahead
+        assert!(!game.is_legal(&straight(Coordinate::new(2, 2))));
+        assert!(!game.is_legal(&straight(Coordinate::new(1, 2))));
+        // wrong tile, or out of reach
+        assert!(!game.is_legal(&Move::Straight {
+            moving_tile: white_two,
+            start: Coordinate::new(1, 2),
+            stop: Coordinate::new(1, 4),
+        }));
+        assert!(!game.is_legal(&straight(Coordinate::new(0, 6))));
+    }
+
+    #[test]
+    fn is_legal_agrees_with_valid_moves() {
+        for game in [Game::new(), Game::from_fen("7/7/7/7/1>4Z/2z4/3|3/2+4/2D4").expect("FEN is valid.")] {
+            for tile_move in game.valid_moves() {
+                assert!(game.is_legal(&tile_move));
+            }
+            let mut passed = game;
+            passed.set_current_player(game.current_player().opponent());
+            for tile_move in passed.valid_moves() {
+                assert!(!game.is_legal(&tile_move));
+            }
+        }
+    }
+
+    #[test]
+    fn apply_legal_move_sequence() {
+        let mut expected = Game::new();
+        let first_move = expected.valid_moves()[0];
+        expected.make_move(&first_move);
+        let second_move = expected.valid_moves()[0];
+        expected.make_move(&second_move);
+
+        let mut game = Game::new();
+        assert_eq!(game.apply_moves(&[first_move, second_move]), Ok(()));
+        assert_eq!(game, expected);
+    }
+
+    #[test]
+    fn parse_move_finds_valid_moves_only() {
+        let game = Game::new();
+        let first_move = game.valid_moves()[0];
+
+        assert_eq!(game.parse_move(&first_move.to_string()), Some(first_move));
+        assert_eq!(game.parse_move("Zc2c9"), None);
+        assert_eq!(game.parse_move(""), None);
+    }
+
+    #[test]
+    fn apply_moves_reports_first_illegal_move() {
+        let first_move = Game::new().valid_moves()[0];
+
+        let mut game = Game::new();
+        assert_eq!(game.apply_moves(&[first_move, first_move]), Err((1, MoveError::Illegal)));
+        assert_eq!(game, Game::new());
+    }
+
+    #[test]
+    fn quiet_move_changes_two_squares() {
+        let before = Game::new();
+        let mut after = before;
+        after.make_move(&before.valid_moves()[0]);
+
+        assert_eq!(before.square_difference_count(&before), 0);
+        assert_eq!(before.square_difference_count(&after), 2);
+    }
+
+    #[test]
+    fn setting_current_player_changes_moving_side() {
+        let mut game = Game::new();
+        assert_eq!(game.current_player(), Player::White);
+
+        game.set_current_player(Player::Brown);
+
+        assert_eq!(game.current_player(), Player::Brown);
+        assert_eq!(game.valid_moves().len(), 28);
+        for brown_move in game.valid_moves() {
+            let Move::Straight { moving_tile, .. } = brown_move else {
+                panic!("Start position only has straight moves.");
+            };
+            assert_eq!(moving_tile.player, Player::Brown);
+        }
+    }
+
+    #[test]
+    fn lazy_moves_match_valid_moves() {
+        let game = Game::new();
+        let lazy_moves: Vec<Move> = game.moves_iter().collect();
+
+        assert_eq!(lazy_moves, game.valid_moves());
+        assert_eq!(game.moves_iter().take(3).count(), 3);
+    }
+
+    #[test]
+    fn hashed_perft_matches_perft() {
+        let mut cache = HashMap::new();
+        let game = Game::new();
+
+        assert_eq!(game.perft(1), 28);
+        for depth in 1..=3 {
+            assert_eq!(game.perft_hashed(depth, &mut cache), game.perft(depth));
+        }
+    }
+
+    #[test]
+    fn moves_in_positions_beyond_the_supply_can_be_played() {
+        let game = Game::from_fen("3z3/7/7/7/7/7/7/7/ZZZ4 w").expect("FEN is valid.");
+        assert!(game.check_invariants().is_err());
+
+        assert!(game.perft(2) > 0);
+        assert_eq!(game.moves_with_results().len(), game.valid_moves().len());
     }
 
     #[test]
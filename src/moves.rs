@@ -0,0 +1,90 @@
+use std::io::{self, Write};
+
+use crate::{FenError, Game};
+
+/// Writes every valid move of the position `fen` on its own line to `output`, followed by the number of moves.
+///
+/// Returns the `FenError` without listing anything if `fen` cannot be parsed.
+///
+/// # Errors
+/// Produces an `io::Error`, if writing to `output` fails.
+///
+pub(crate) fn list_moves<T: Write>(fen: &str, output: &mut T) -> io::Result<Result<(), FenError>> {
+    let game = match Game::from_fen(fen) {
+        Ok(game) => game,
+        Err(error) => return Ok(Err(error)),
+    };
+
+    let moves = game.valid_moves();
+    for tile_move in &moves {
+        writeln!(output, "{tile_move}")?;
+    }
+    writeln!(output, "{}", moves.len())?;
+
+    Ok(Ok(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::list_moves;
+    use crate::{FenError, INITIAL_FEN_STRING};
+
+    #[test]
+    fn list_start_position_moves() {
+        let mut output = Vec::new();
+        assert!(list_moves(INITIAL_FEN_STRING, &mut output)
+            .expect("Writing to a Vec cannot fail.")
+            .is_ok());
+
+        let text = String::from_utf8(output).expect("Output is UTF-8.");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 29);
+        assert_eq!(lines.last(), Some(&"28"));
+        assert!(lines[..28].iter().all(|line| !line.is_empty()));
+    }
+
+    #[test]
+    fn reject_invalid_fen() {
+        let mut output = Vec::new();
+        let error = list_moves("7/7/q", &mut output)
+            .expect("Writing to a Vec cannot fail.")
+            .expect_err("FEN is invalid.");
+
+        assert!(matches!(error, FenError::InvalidChar { char_index: 4 }));
+        assert_eq!(error.to_string(), "Board contains invalid char at index 4");
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn list_barragoon_captures() {
+        let mut output = Vec::new();
+        assert!(list_moves("7/7/7/7/7/7/7/7/D2+3", &mut output)
+            .expect("Writing to a Vec cannot fail.")
+            .is_ok());
+
+        let text = String::from_utf8(output).expect("Output is UTF-8.");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert!(lines.contains(&"a1xd1"));
+        assert_eq!(lines.last(), Some(&"7"));
+    }
+
+    #[test]
+    fn reject_boards_of_the_wrong_size() {
+        let mut output = Vec::new();
+
+        let error = list_moves("ZZZZZZZZ/7/7/7/7/7/7/7/7", &mut output)
+            .expect("Writing to a Vec cannot fail.")
+            .expect_err("Rank is too long.");
+        assert!(matches!(error, FenError::OverfullLine { char_index: 7 }));
+        let error = list_moves("Z", &mut output)
+            .expect("Writing to a Vec cannot fail.")
+            .expect_err("Ranks are missing.");
+        assert!(matches!(error, FenError::TooFewLines { char_index: 1 }));
+        let error = list_moves("7/7/7/7/7/7/7/7/6", &mut output)
+            .expect("Writing to a Vec cannot fail.")
+            .expect_err("Last rank is short.");
+        assert!(matches!(error, FenError::UnderfullLine { char_index: 17 }));
+        assert!(output.is_empty());
+    }
+}
@@ -1,8 +1,9 @@
+use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
 use std::ops;
 
-use crate::{BOARD_HEIGHT, BOARD_WIDTH, FILE_NAMES, RANK_NAMES};
+use crate::{Game, BOARD_HEIGHT, BOARD_WIDTH, FILE_NAMES, RANK_NAMES};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumIter)]
 pub enum Direction {
@@ -33,6 +34,29 @@ impl Direction {
         }
     }
 
+    #[must_use]
+    pub const fn opposite(&self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::East => Self::West,
+            Self::South => Self::North,
+            Self::West => Self::East,
+        }
+    }
+
+    /// The direction as seen in a mirror placed along the files, i.e. with West and East swapped.
+    #[must_use]
+    pub const fn mirror_files(&self) -> Self {
+        match self {
+            Self::North => Self::North,
+            Self::East => Self::West,
+            Self::South => Self::South,
+            Self::West => Self::East,
+        }
+    }
+
+    /// Rank 0 is White's home rank at the bottom of the board, so going North increases the rank and going East
+    /// increases the file.
     #[must_use]
     pub const fn as_delta(&self) -> PositionDelta {
         match self {
@@ -90,6 +114,56 @@ impl Coordinate {
     pub const fn new(rank: u8, file: u8) -> Self {
         Self { rank, file }
     }
+
+    /// Position of the square when counting the squares rank by rank, starting at a1 with 0.
+    #[must_use]
+    pub const fn index(&self) -> u8 {
+        self.rank * BOARD_WIDTH + self.file
+    }
+
+    /// Inverse of `index`. Returns `None` for indices beyond the last square.
+    #[must_use]
+    pub const fn from_index(index: u8) -> Option<Self> {
+        if index < BOARD_WIDTH * BOARD_HEIGHT {
+            Some(Self::new(index / BOARD_WIDTH, index % BOARD_WIDTH))
+        } else {
+            None
+        }
+    }
+
+    /// The square in the same place after turning the board by 180 degrees.
+    #[must_use]
+    pub const fn rotate_180(&self) -> Self {
+        Self::new(BOARD_HEIGHT - 1 - self.rank, BOARD_WIDTH - 1 - self.file)
+    }
+
+    /// The square in the same place after mirroring the board along the files, i.e. swapping West and East.
+    #[must_use]
+    pub const fn mirror_files(&self) -> Self {
+        Self::new(self.rank, BOARD_WIDTH - 1 - self.file)
+    }
+
+    /// Parses a square name like `c4`. Returns `None` for anything that is not a square of the board.
+    #[must_use]
+    pub fn from_algebraic(name: &str) -> Option<Self> {
+        let mut chars = name.chars();
+        let (file_name, rank_name) = (chars.next()?, chars.next()?);
+        if chars.next().is_some() {
+            return None;
+        }
+
+        let file = FILE_NAMES.iter().position(|&name| name == file_name)?;
+        let rank = RANK_NAMES.iter().position(|&name| name == rank_name)?;
+        Some(Self::new(u8::try_from(rank).ok()?, u8::try_from(file).ok()?))
+    }
+
+    /// Yields the orthogonally adjacent squares that lie on the board, together with the direction leading there.
+    pub fn neighbors(&self) -> impl Iterator<Item = (Direction, Self)> {
+        let origin = *self;
+        Direction::iter()
+            .map(move |direction| (direction, origin + direction.as_delta()))
+            .filter(|(_, neighbor)| Game::contains_coordinate(neighbor))
+    }
 }
 
 impl std::fmt::Display for Coordinate {
@@ -108,6 +182,12 @@ impl std::fmt::Display for Coordinate {
     }
 }
 
+/// Formats the range of squares between `from` and `to` in algebraic notation, e.g. `c2-c5`.
+#[must_use]
+pub fn format_square_range(from: Coordinate, to: Coordinate) -> String {
+    format!("{from}-{to}")
+}
+
 impl ops::Add<PositionDelta> for Coordinate {
     type Output = Self;
 
@@ -155,18 +235,44 @@ impl ops::Sub<Self> for PositionDelta {
     }
 }
 
+/// Scales a delta component-wise. Results outside of `i8::MIN..=i8::MAX` saturate at the bounds instead of
+/// overflowing; any delta that stays on the board is far within that range.
 impl ops::Mul<i8> for PositionDelta {
     type Output = Self;
 
-    #[allow(clippy::cast_possible_wrap)]
     fn mul(self, rhs: i8) -> Self::Output {
-        Self::new(self.rank_delta * rhs, self.file_delta * rhs)
+        Self::new(self.rank_delta.saturating_mul(rhs), self.file_delta.saturating_mul(rhs))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::SquareContent;
+
+    #[test]
+    fn north_leads_away_from_rank_one() {
+        let mut square = Coordinate::new(0, 3);
+        for rank in 1..BOARD_HEIGHT {
+            square = square + Direction::North.as_delta();
+            assert_eq!(square, Coordinate::new(rank, 3));
+            assert_eq!(square.to_string(), format!("d{}", RANK_NAMES[rank as usize]));
+        }
+        assert_eq!(square.to_string(), "d9");
+        assert_eq!((Coordinate::new(0, 0) + Direction::East.as_delta()).to_string(), "b1");
+    }
+
+    #[test]
+    fn square_names_agree_with_fen_layout() {
+        let game = Game::from_fen("Z6/7/7/7/7/7/7/7/6z").expect("FEN is valid.");
+        let top_left = Coordinate::from_algebraic("a9").expect("a9 is a square.");
+        let bottom_right = Coordinate::from_algebraic("g1").expect("g1 is a square.");
+
+        assert_eq!(top_left, Coordinate::new(BOARD_HEIGHT - 1, 0));
+        assert_eq!(game.get_content(&top_left).as_fen_char(), 'Z');
+        assert_eq!(game.get_content(&bottom_right).as_fen_char(), 'z');
+        assert_eq!(*game.get_content(&(top_left + Direction::South.as_delta())), SquareContent::Empty);
+    }
 
     #[test]
     fn adding_deltas() {
@@ -199,6 +305,72 @@ mod tests {
     }
 
     #[test]
+    fn multiply_deltas_saturates() {
+        assert_eq!(PositionDelta::new(4, -4) * 40, PositionDelta::new(i8::MAX, i8::MIN));
+        assert_eq!(PositionDelta::new(4, -4) * 31, PositionDelta::new(124, -124));
+        assert_eq!(PositionDelta::new(i8::MIN, 1) * -1, PositionDelta::new(i8::MAX, -1));
+    }
+
+    #[test]
+    fn board_transforms_are_involutions() {
+        for index in 0..BOARD_WIDTH * BOARD_HEIGHT {
+            let square = Coordinate::from_index(index).expect("Index is on the board.");
+            assert!(Game::contains_coordinate(&square.rotate_180()));
+            assert_eq!(square.rotate_180().rotate_180(), square);
+            assert_eq!(square.mirror_files().mirror_files(), square);
+        }
+
+        assert_eq!(Coordinate::new(0, 0).rotate_180().to_string(), "g9");
+        assert_eq!(Coordinate::new(8, 0).rotate_180().to_string(), "g1");
+        assert_eq!(Coordinate::new(4, 3).rotate_180(), Coordinate::new(4, 3));
+        assert_eq!(Coordinate::new(2, 1).mirror_files().to_string(), "f3");
+    }
+
+    #[test]
+    fn square_indices_round_trip() {
+        for index in 0..BOARD_WIDTH * BOARD_HEIGHT {
+            let coordinate = Coordinate::from_index(index).expect("Index is on the board.");
+            assert_eq!(coordinate.index(), index);
+        }
+        assert_eq!(Coordinate::new(1, 0).index(), 7);
+        assert_eq!(Coordinate::from_index(63), None);
+    }
+
+    #[test]
+    fn parse_algebraic_squares() {
+        assert_eq!(Coordinate::from_algebraic("a1"), Some(Coordinate::new(0, 0)));
+        assert_eq!(Coordinate::from_algebraic("c4"), Some(Coordinate::new(3, 2)));
+        assert_eq!(Coordinate::from_algebraic("g9"), Some(Coordinate::new(8, 6)));
+        assert_eq!(Coordinate::from_algebraic("h1"), None);
+        assert_eq!(Coordinate::from_algebraic("a0"), None);
+        assert_eq!(Coordinate::from_algebraic("a10"), None);
+        assert_eq!(Coordinate::from_algebraic("a"), None);
+    }
+
+    #[test]
+    fn format_ranges() {
+        assert_eq!(format_square_range(Coordinate::new(1, 2), Coordinate::new(4, 2)), "c2-c5");
+        assert_eq!(format_square_range(Coordinate::new(8, 0), Coordinate::new(8, 6)), "a9-g9");
+    }
+
+    #[test]
+    fn corner_has_two_neighbors() {
+        let neighbors: Vec<(Direction, Coordinate)> = Coordinate::new(0, 0).neighbors().collect();
+
+        assert_eq!(
+            neighbors,
+            vec![(Direction::North, Coordinate::new(1, 0)), (Direction::East, Coordinate::new(0, 1))]
+        );
+    }
+
+    #[test]
+    fn edge_and_center_neighbor_counts() {
+        assert_eq!(Coordinate::new(4, 3).neighbors().count(), 4);
+        assert_eq!(Coordinate::new(8, 3).neighbors().count(), 3);
+    }
+
+    #[test]
+    #[allow(clippy::erasing_op)]
     fn multiply_deltas() {
         assert_eq!(PositionDelta::new(4, 3) * 1, PositionDelta::new(4, 3));
         assert_eq!(PositionDelta::new(4, 3) * -1, PositionDelta::new(-4, -3));
@@ -0,0 +1,90 @@
+use crate::{Game, Move};
+
+/// Chooses the move to play in a position. Returns `None` if the side to move has no valid move.
+#[allow(dead_code)]
+pub(crate) trait MovePicker {
+    fn pick(&mut self, game: &Game) -> Option<Move>;
+}
+
+/// Picks uniformly among the valid moves, using a xorshift generator seeded on construction so that games can be
+/// reproduced.
+#[allow(dead_code)]
+pub(crate) struct RandomPicker {
+    state: u64,
+}
+
+impl RandomPicker {
+    #[allow(dead_code)]
+    pub const fn new(seed: u64) -> Self {
+        // xorshift gets stuck on 0
+        Self {
+            state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed },
+        }
+    }
+
+    #[allow(dead_code)]
+    const fn next_random(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
+
+impl MovePicker for RandomPicker {
+    fn pick(&mut self, game: &Game) -> Option<Move> {
+        let moves = game.valid_moves();
+        if moves.is_empty() {
+            return None;
+        }
+
+        let index = self.next_random() % u64::try_from(moves.len()).expect("Move count fits into u64.");
+        moves.get(usize::try_from(index).expect("Index is below the move count.")).copied()
+    }
+}
+
+/// Always picks the first of the valid moves.
+#[allow(dead_code)]
+pub(crate) struct FirstLegalPicker;
+
+impl MovePicker for FirstLegalPicker {
+    fn pick(&mut self, game: &Game) -> Option<Move> {
+        game.moves_iter().next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(picker: &mut impl MovePicker, plies: usize) -> Vec<Move> {
+        let mut game = Game::new();
+        let mut moves = vec![];
+        for _ in 0..plies {
+            let Some(next_move) = picker.pick(&game) else {
+                break;
+            };
+            game.make_move(&next_move);
+            moves.push(next_move);
+        }
+        moves
+    }
+
+    #[test]
+    fn random_picker_is_reproducible() {
+        let first_run = play(&mut RandomPicker::new(7), 12);
+
+        assert_eq!(first_run.len(), 12);
+        assert_eq!(play(&mut RandomPicker::new(7), 12), first_run);
+        assert_ne!(play(&mut RandomPicker::new(8), 12), first_run);
+    }
+
+    #[test]
+    fn first_legal_picker_takes_first_valid_move() {
+        let game = Game::new();
+
+        assert_eq!(FirstLegalPicker.pick(&game), game.valid_moves().first().copied());
+        assert_eq!(FirstLegalPicker.pick(&Game::empty()), None);
+        assert_eq!(RandomPicker::new(1).pick(&Game::empty()), None);
+    }
+}
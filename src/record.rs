@@ -0,0 +1,223 @@
+use crate::{Game, Move, Player};
+
+/// Events ending a game apart from the moves on the board.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[allow(dead_code)]
+pub(crate) enum GameEvent {
+    Resign(Player),
+    DrawAgreed,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[allow(dead_code)]
+pub(crate) enum GameResult {
+    Ongoing,
+    Win(Player),
+    Draw,
+}
+
+impl GameResult {
+    /// The result as written at the end of a PGN game record.
+    #[allow(dead_code)]
+    pub const fn to_result_token(self) -> &'static str {
+        match self {
+            Self::Ongoing => "*",
+            Self::Win(Player::White) => "1-0",
+            Self::Win(Player::Brown) => "0-1",
+            Self::Draw => "1/2-1/2",
+        }
+    }
+}
+
+impl std::fmt::Display for GameResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ongoing => write!(f, "Ongoing"),
+            Self::Win(Player::White) => write!(f, "White wins"),
+            Self::Win(Player::Brown) => write!(f, "Brown wins"),
+            Self::Draw => write!(f, "Draw"),
+        }
+    }
+}
+
+/// A played game, stored as its start position and the moves played from there.
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub(crate) struct GameRecord {
+    start: Game,
+    moves: Vec<Move>,
+    final_event: Option<GameEvent>,
+    progress_limit: Option<usize>,
+}
+
+impl GameRecord {
+    #[allow(dead_code)]
+    pub const fn new(start: Game) -> Self {
+        Self {
+            start,
+            moves: vec![],
+            final_event: None,
+            progress_limit: None,
+        }
+    }
+
+    /// Declares the game drawn once `limit` plies in a row were played without a capture.
+    #[allow(dead_code)]
+    pub const fn with_progress_limit(mut self, limit: usize) -> Self {
+        self.progress_limit = Some(limit);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn push(&mut self, played_move: Move) {
+        self.moves.push(played_move);
+    }
+
+    #[allow(dead_code)]
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Records a resignation or draw agreement. Only the first event counts, as it already ends the game.
+    #[allow(dead_code)]
+    pub const fn push_event(&mut self, event: GameEvent) {
+        if self.final_event.is_none() {
+            self.final_event = Some(event);
+        }
+    }
+
+    /// Number of plies played since the last capture of a tile or barragoon, or since the start if there was none.
+    #[allow(dead_code)]
+    pub fn plies_since_progress(&self) -> usize {
+        self.moves
+            .iter()
+            .rev()
+            .take_while(|played_move| matches!(played_move, Move::Straight { .. }))
+            .count()
+    }
+
+    #[allow(dead_code)]
+    pub fn game_result(&self) -> GameResult {
+        match self.final_event {
+            None if self.progress_limit.is_some_and(|limit| self.plies_since_progress() >= limit) => GameResult::Draw,
+            None => GameResult::Ongoing,
+            Some(GameEvent::Resign(player)) => GameResult::Win(player.opponent()),
+            Some(GameEvent::DrawAgreed) => GameResult::Draw,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn final_position(&self) -> Game {
+        let mut game = self.start;
+        for played_move in &self.moves {
+            game.make_move(played_move);
+        }
+        game
+    }
+
+    /// Every position of the game in order, from the start position up to and including the final one.
+    #[allow(dead_code)]
+    pub fn positions(&self) -> Vec<Game> {
+        let mut game = self.start;
+        let mut positions = vec![game];
+
+        for played_move in &self.moves {
+            game.make_move(played_move);
+            positions.push(game);
+        }
+
+        positions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_replay_the_record() {
+        let mut record = GameRecord::new(Game::new());
+        let mut game = Game::new();
+        for _ in 0..4 {
+            let next_move = game.valid_moves()[0];
+            game.make_move(&next_move);
+            record.push(next_move);
+        }
+
+        let positions = record.positions();
+
+        assert_eq!(positions.len(), record.moves().len() + 1);
+        assert_eq!(positions.first(), Some(&Game::new()));
+        assert_eq!(positions.last(), Some(&record.final_position()));
+        assert_eq!(record.final_position(), game);
+    }
+
+    #[test]
+    fn resignation_wins_for_opponent() {
+        let mut record = GameRecord::new(Game::new());
+        assert_eq!(record.game_result(), GameResult::Ongoing);
+
+        record.push_event(GameEvent::Resign(Player::Brown));
+        record.push_event(GameEvent::DrawAgreed);
+
+        assert_eq!(record.game_result(), GameResult::Win(Player::White));
+    }
+
+    #[test]
+    fn quiet_moves_past_the_limit_draw() {
+        let mut record = GameRecord::new(Game::new()).with_progress_limit(4);
+        let mut game = Game::new();
+        for plies in 0..4 {
+            assert_eq!(record.game_result(), GameResult::Ongoing);
+            let quiet_move = *game
+                .valid_moves()
+                .iter()
+                .find(|candidate| matches!(candidate, Move::Straight { .. }))
+                .expect("Position has a quiet move.");
+            game.make_move(&quiet_move);
+            record.push(quiet_move);
+            assert_eq!(record.plies_since_progress(), plies + 1);
+        }
+
+        assert_eq!(record.game_result(), GameResult::Draw);
+    }
+
+    #[test]
+    fn captures_reset_progress() {
+        let start = Game::from_fen("7/7/7/7/7/7/1z5/7/1Z5").expect("FEN is valid.");
+        let capture = *start
+            .valid_moves()
+            .iter()
+            .find(|candidate| matches!(candidate, Move::TileCapture { .. }))
+            .expect("Position has a capture.");
+        let mut record = GameRecord::new(start).with_progress_limit(1);
+        record.push(capture);
+
+        assert_eq!(record.plies_since_progress(), 0);
+        assert_eq!(record.game_result(), GameResult::Ongoing);
+    }
+
+    #[test]
+    fn results_print_as_text_and_tokens() {
+        let results = [
+            GameResult::Ongoing,
+            GameResult::Win(Player::White),
+            GameResult::Win(Player::Brown),
+            GameResult::Draw,
+        ];
+
+        assert_eq!(results.map(GameResult::to_result_token), ["*", "1-0", "0-1", "1/2-1/2"]);
+        assert_eq!(
+            results.map(|result| result.to_string()),
+            ["Ongoing", "White wins", "Brown wins", "Draw"]
+        );
+    }
+
+    #[test]
+    fn agreed_draw_is_a_draw() {
+        let mut record = GameRecord::new(Game::new());
+        record.push_event(GameEvent::DrawAgreed);
+
+        assert_eq!(record.game_result(), GameResult::Draw);
+    }
+}
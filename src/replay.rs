@@ -0,0 +1,92 @@
+use std::io::{self, BufRead, Write};
+
+use crate::Game;
+
+/// Replays the moves read line by line from `input`, starting at the initial position. After every move the
+/// resulting FEN and the number of legal moves for the next player are written to `output`.
+///
+/// Returns the (1-based) line number of the first illegal move, or `None` if all moves could be played.
+///
+/// # Errors
+/// Produces an `io::Error`, if reading from `input` or writing to `output` fails.
+///
+pub fn replay<S, T>(input: &mut S, output: &mut T) -> io::Result<Option<usize>>
+where
+    S: BufRead,
+    T: Write,
+{
+    let mut game = Game::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        let move_string = line.trim();
+        if move_string.is_empty() {
+            continue;
+        }
+
+        if let Some(played_move) = game.parse_move(move_string) {
+            game.make_move(&played_move);
+            writeln!(output, "{} {}", game.as_fen(), game.valid_moves().len())?;
+        } else {
+            writeln!(output, "Illegal move '{move_string}' in line {}.", index + 1)?;
+            return Ok(Some(index + 1));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::replay;
+    use crate::Game;
+
+    #[test]
+    fn replay_legal_sequence() {
+        let mut game = Game::new();
+        let first_move = game.valid_moves()[0];
+        game.make_move(&first_move);
+        let second_move = game.valid_moves()[0];
+        let input = format!("{first_move}\n\n{second_move}\n");
+        let mut output = Vec::new();
+
+        let result = replay(&mut input.as_bytes(), &mut output).expect("Replaying from memory cannot fail.");
+
+        let output = String::from_utf8(output).expect("Output is valid UTF-8.");
+        assert_eq!(result, None);
+        assert_eq!(output.lines().count(), 2);
+        assert!(output.lines().all(|line| line.split_whitespace().count() == 3));
+    }
+
+    #[test]
+    fn replay_stops_at_illegal_move() {
+        let first_move = Game::new().valid_moves()[0];
+        let input = format!("{first_move}\n{first_move}\n{first_move}\n");
+        let mut output = Vec::new();
+
+        let result = replay(&mut input.as_bytes(), &mut output).expect("Replaying from memory cannot fail.");
+
+        let output = String::from_utf8(output).expect("Output is valid UTF-8.");
+        assert_eq!(result, Some(2));
+        assert_eq!(
+            output.lines().last(),
+            Some(format!("Illegal move '{first_move}' in line 2.").as_str())
+        );
+    }
+
+    #[test]
+    fn replay_barragoon_capture() {
+        let input = "Vb1a3\nzc8b7\na3xc5\n";
+        let mut output = Vec::new();
+
+        let result = replay(&mut input.as_bytes(), &mut output).expect("Replaying from memory cannot fail.");
+
+        let output = String::from_utf8(output).expect("Output is valid UTF-8.");
+        assert_eq!(result, None);
+        assert_eq!(output.lines().count(), 3);
+        let last_line = output.lines().last().expect("Three lines were written.");
+        let (fen, _) = last_line.rsplit_once(' ').expect("The line ends with the move count.");
+        let game = Game::from_fen(fen).expect("Replay writes valid FENs.");
+        assert_eq!(game.barragoons().len(), Game::new().barragoons().len() - 1);
+    }
+}
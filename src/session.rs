@@ -0,0 +1,140 @@
+use std::collections::VecDeque;
+
+use crate::{Game, Move, MoveError};
+
+#[allow(dead_code)]
+const LEGALITY_CACHE_SIZE: usize = 64;
+
+/// A game as seen by an interactive client, which tends to ask about the same candidate moves over and over. Legality
+/// answers are kept in a small least recently used cache keyed by position hash and move.
+#[allow(dead_code)]
+pub(crate) struct Session {
+    game: Game,
+    legality_cache: VecDeque<((u64, Move), bool)>,
+    cache_hits: usize,
+}
+
+impl Session {
+    #[allow(dead_code)]
+    pub const fn new(game: Game) -> Self {
+        Self {
+            game,
+            legality_cache: VecDeque::new(),
+            cache_hits: 0,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub const fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Number of `is_legal` calls answered from the cache so far.
+    #[allow(dead_code)]
+    pub const fn cache_hits(&self) -> usize {
+        self.cache_hits
+    }
+
+    #[allow(dead_code)]
+    pub fn is_legal(&mut self, tile_move: &Move) -> bool {
+        let key = (self.game.position_hash(), *tile_move);
+
+        if let Some(index) = self.legality_cache.iter().position(|(cached_key, _)| *cached_key == key) {
+            let entry = self.legality_cache.remove(index).expect("Index was just found.");
+            self.legality_cache.push_front(entry);
+            self.cache_hits += 1;
+            return entry.1;
+        }
+
+        let legal = self.game.is_legal(tile_move);
+        if self.legality_cache.len() == LEGALITY_CACHE_SIZE {
+            self.legality_cache.pop_back();
+        }
+        self.legality_cache.push_front((key, legal));
+        legal
+    }
+
+    /// Plays `tile_move` and drops all cached answers, as they belong to the previous position.
+    ///
+    /// # Errors
+    /// Returns `MoveError::Illegal` and keeps the position if the move is not legal.
+    #[allow(dead_code)]
+    pub fn try_make_move(&mut self, tile_move: &Move) -> Result<(), MoveError> {
+        self.game.try_make_move(tile_move)?;
+        self.legality_cache.clear();
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn set_position(&mut self, game: Game) {
+        self.game = game;
+        self.legality_cache.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::Coordinate;
+
+    #[test]
+    fn repeated_checks_hit_the_cache() {
+        let mut session = Session::new(Game::new());
+        let candidate = Game::new().valid_moves()[0];
+
+        assert!(session.is_legal(&candidate));
+        assert_eq!(session.cache_hits(), 0);
+        assert!(session.is_legal(&candidate));
+        assert!(session.is_legal(&candidate));
+        assert_eq!(session.cache_hits(), 2);
+    }
+
+    #[test]
+    fn moving_invalidates_the_cache() {
+        let mut session = Session::new(Game::new());
+        let candidate = Game::new().valid_moves()[0];
+
+        assert!(session.is_legal(&candidate));
+        session.try_make_move(&candidate).expect("Move is legal.");
+
+        assert!(!session.is_legal(&candidate));
+        assert_eq!(session.cache_hits(), 0);
+    }
+
+    #[test]
+    fn least_recently_used_answers_are_evicted() {
+        let mut session = Session::new(Game::new());
+        let moves = Game::new().valid_moves();
+        let first = moves[0];
+
+        session.is_legal(&first);
+        let empty = Game::empty();
+        let fillers = empty.squares().flat_map(|square| {
+            [Coordinate::new(0, 0), Coordinate::new(0, 1)].map(|stop| Move::BarragoonCapture {
+                start: square.coordinate,
+                stop,
+            })
+        });
+        for filler in fillers.take(LEGALITY_CACHE_SIZE) {
+            session.is_legal(&filler);
+        }
+        session.is_legal(&first);
+
+        assert_eq!(session.cache_hits(), 0);
+    }
+
+    #[test]
+    fn copied_games_leave_the_cache_alone() {
+        let mut session = Session::new(Game::new());
+        let candidate = Game::new().valid_moves()[0];
+        assert!(session.is_legal(&candidate));
+
+        let mut copy = *session.game();
+        copy.make_move(&candidate);
+        assert!(!copy.is_legal(&candidate));
+
+        assert!(session.is_legal(&candidate));
+        assert_eq!(session.cache_hits(), 1);
+        assert_eq!(*session.game(), Game::new());
+    }
+}
@@ -1,7 +1,8 @@
 use std::fmt::Write;
 use std::hash::Hash;
 
-use crate::navigation::{Direction, PositionDelta};
+use crate::navigation::{Coordinate, Direction, PositionDelta};
+use crate::Game;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
@@ -31,6 +32,15 @@ impl TileType {
         }
     }
 
+    /// Number of tiles of this type each player owns at the start of a game.
+    #[must_use]
+    pub const fn supply_per_player(&self) -> u8 {
+        match self {
+            Self::Two | Self::Four => 2,
+            Self::Three => 3,
+        }
+    }
+
     fn make_strides(&self, are_full_strides: bool) -> Vec<Stride> {
         let stride_length = if are_full_strides {
             self.full_stride_length()
@@ -82,9 +92,22 @@ impl TileType {
 
         all_strides
     }
+
+    /// Every square any stride of this tile type could pass or end on from `origin`, ignoring what stands on the
+    /// board. Each square is represented by the bit at its `Coordinate::index`.
+    #[must_use]
+    pub fn reach_mask(&self, origin: Coordinate) -> u64 {
+        self.all_strides()
+            .iter()
+            .flat_map(Stride::steps)
+            .map(|step| step.square_from(origin))
+            .filter(Game::contains_coordinate)
+            .fold(0, |mask, square| mask | 1 << square.index())
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[allow(clippy::struct_field_names)]
 pub struct Stride {
     start_direction: Direction,
     start_length: u8,
@@ -125,6 +148,14 @@ pub struct Step {
     pub position_delta: PositionDelta,
 }
 
+impl Step {
+    /// The square entered by this step for a stride starting at `origin`.
+    #[must_use]
+    pub fn square_from(&self, origin: Coordinate) -> Coordinate {
+        origin + self.position_delta
+    }
+}
+
 impl std::fmt::Display for Step {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut leave_str = String::new();
@@ -137,7 +168,7 @@ impl std::fmt::Display for Step {
     }
 }
 
-impl<'a> Iterator for StrideIterator<'a> {
+impl Iterator for StrideIterator<'_> {
     type Item = Step;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -171,8 +202,16 @@ impl<'a> Iterator for StrideIterator<'a> {
 
         result
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let total_length = self.ref_stride.start_length + self.ref_stride.bend_length;
+        let remaining = usize::from(total_length.saturating_sub(self.index));
+        (remaining, Some(remaining))
+    }
 }
 
+impl ExactSizeIterator for StrideIterator<'_> {}
+
 impl Stride {
     #[must_use]
     pub const fn new_bend(
@@ -203,7 +242,7 @@ impl Stride {
     }
 
     #[must_use]
-    pub const fn steps(&self) -> StrideIterator {
+    pub const fn steps(&self) -> StrideIterator<'_> {
         StrideIterator::new(self)
     }
 
@@ -212,6 +251,34 @@ impl Stride {
     pub fn full_delta(&self) -> PositionDelta {
         self.start_direction.as_delta() * self.start_length as i8 + self.bend_direction.as_delta() * self.bend_length as i8
     }
+
+    /// Draws the squares covered by the stride as a small grid with north at the top. The origin is marked with `o`,
+    /// covered squares with `#` and uncovered squares inside the bounding box with `.`.
+    #[must_use]
+    pub fn render_path(&self) -> String {
+        let deltas: Vec<PositionDelta> = self.steps().map(|step| step.position_delta).collect();
+
+        let ranks = deltas.iter().map(|delta| delta.rank_delta).chain([0]);
+        let files = deltas.iter().map(|delta| delta.file_delta).chain([0]);
+        let (min_rank, max_rank) = (ranks.clone().min().unwrap_or(0), ranks.max().unwrap_or(0));
+        let (min_file, max_file) = (files.clone().min().unwrap_or(0), files.max().unwrap_or(0));
+
+        let mut grid = String::new();
+        for rank in (min_rank..=max_rank).rev() {
+            for file in min_file..=max_file {
+                let cell = PositionDelta::new(rank, file);
+                if cell == PositionDelta::zero() {
+                    grid.push('o');
+                } else if deltas.contains(&cell) {
+                    grid.push('#');
+                } else {
+                    grid.push('.');
+                }
+            }
+            grid.push('\n');
+        }
+        grid
+    }
 }
 
 impl std::fmt::Display for Stride {
@@ -256,12 +323,90 @@ mod tests {
     fn strides_are_of_proper_length() {
         for tile_type in TileType::iter() {
             for stride in tile_type.full_strides() {
-                let steps: Vec<Step> = stride.steps().collect();
-                assert_eq!(steps.len(), tile_type.full_stride_length() as usize);
+                assert_eq!(stride.steps().count(), tile_type.full_stride_length() as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn stride_iterators_know_their_length() {
+        for tile_type in TileType::iter() {
+            for stride in tile_type.full_strides() {
+                assert_eq!(stride.steps().len(), tile_type.full_stride_length() as usize);
+            }
+            for stride in tile_type.short_strides() {
+                assert_eq!(stride.steps().len(), tile_type.short_stride_length() as usize);
             }
         }
     }
 
+    #[test]
+    fn stride_iterator_length_shrinks_while_stepping() {
+        let stride = Stride::new_bend(Direction::North, 1, Direction::East, 2, true);
+        let mut steps = stride.steps();
+
+        assert_eq!(steps.len(), 3);
+        steps.next();
+        assert_eq!(steps.len(), 2);
+        steps.next();
+        steps.next();
+        assert_eq!(steps.len(), 0);
+        assert_eq!(steps.next(), None);
+        assert_eq!(steps.len(), 0);
+    }
+
+    #[test]
+    fn last_step_enters_stride_target() {
+        let stride = Stride::new_straight(Direction::East, 3, true);
+        let last_step = stride.steps().last().expect("Stride has steps.");
+
+        assert_eq!(last_step.square_from(Coordinate::new(2, 1)), Coordinate::new(2, 4));
+    }
+
+    #[test]
+    fn straight_stride_renders_as_row() {
+        assert_eq!(Stride::new_straight(Direction::East, 3, true).render_path(), "o###\n");
+        assert_eq!(Stride::new_straight(Direction::South, 2, false).render_path(), "o\n#\n#\n");
+    }
+
+    #[test]
+    fn bend_stride_renders_its_corner() {
+        assert_eq!(
+            Stride::new_bend(Direction::North, 2, Direction::West, 1, true).render_path(),
+            "##\n.#\n.o\n"
+        );
+    }
+
+    #[test]
+    fn central_two_reaches_its_stride_squares() {
+        let origin = Coordinate::new(4, 3);
+        let expected = [
+            (5, 3),
+            (6, 3),
+            (3, 3),
+            (2, 3),
+            (4, 2),
+            (4, 1),
+            (4, 4),
+            (4, 5),
+            (5, 2),
+            (5, 4),
+            (3, 2),
+            (3, 4),
+        ]
+        .into_iter()
+        .fold(0_u64, |mask, (rank, file)| mask | 1 << (rank * 7 + file));
+
+        assert_eq!(TileType::Two.reach_mask(origin), expected);
+    }
+
+    #[test]
+    fn reach_mask_stays_on_board() {
+        assert_eq!(TileType::Two.reach_mask(Coordinate::new(0, 0)).count_ones(), 5);
+        assert_eq!(TileType::Two.reach_mask(Coordinate::new(8, 6)).count_ones(), 5);
+        assert_eq!(TileType::Four.reach_mask(Coordinate::new(8, 6)) >> 63, 0);
+    }
+
     #[test]
     fn single_tile_on_board_has_all_valid_moves() {
         for tile_type in TileType::iter() {
@@ -7,8 +7,10 @@ use std::{
 };
 
 use crate::application;
-use crate::FenError;
+use crate::navigation::Coordinate;
 use crate::Game;
+use crate::PlacementError;
+use crate::SquareContent;
 
 struct UbiHandler {
     state: UbiState,
@@ -49,7 +51,7 @@ impl UbiHandler {
                 application::VERSION_PATCH,
                 application::AUTHOR_NAME
             )
-            .unwrap();
+            .expect("Writing to a String cannot fail.");
             answers.push(answer);
             answers.push(String::from("ubiok"));
         }
@@ -68,7 +70,7 @@ impl UbiHandler {
                 answers.push(String::from("readyok"));
             }
             _ => answers.push(String::from("readyok")),
-        };
+        }
 
         answers
     }
@@ -78,21 +80,26 @@ impl UbiHandler {
 
         let start_position_mode = args.next();
 
-        if start_position_mode == Some("startpos") {
-            self.game = Game::new();
+        let (position, move_tokens) = if start_position_mode == Some("startpos") {
+            (Ok(Game::new()), Self::collect_residual_fen_args(&mut args).1)
+        } else if start_position_mode == Some("empty") {
+            (Ok(Game::empty()), Self::collect_residual_fen_args(&mut args).1)
         } else if start_position_mode == Some("fen") {
-            let game_result = Game::from_fen(Self::collect_residual_fen_args(&mut args).as_str());
-            match game_result {
-                Ok(game) => self.game = game,
-                Err(FenError::UnderfullLine { char_index: ci }) => answers.push(format!("Board rank is not filled at index {ci}.")),
-                Err(FenError::OverfullLine { char_index: ci }) => answers.push(format!("Board rank is too full at index {ci}.")),
-                Err(FenError::TooManyLines { char_index: ci }) => answers.push(format!("Board has to many ranks at index {ci}.")),
-                Err(FenError::InvalidChar { char_index: ci }) => answers.push(format!("Board contains invalid char at index {ci}.")),
-            }
+            let (fen_string, move_tokens) = Self::collect_residual_fen_args(&mut args);
+            (
+                Game::from_fen(fen_string.as_str()).map_err(|error| format!("{error}.")),
+                move_tokens,
+            )
         } else if let Some(subcommand) = start_position_mode {
-            answers.push(format!("Invalid subcommand {subcommand}."));
+            (Err(format!("Invalid subcommand {subcommand}.")), vec![])
         } else {
-            answers.push("Missing subcommand after 'position'.".to_string());
+            (Err("Missing subcommand after 'position'.".to_string()), vec![])
+        };
+
+        let position = position.and_then(|game| game.check_invariants().map(|()| game));
+        match position.and_then(|mut game| Self::apply_move_tokens(&mut game, &move_tokens).map(|()| game)) {
+            Ok(game) => self.game = game,
+            Err(message) => answers.push(message),
         }
 
         // println!("{}", self.game);
@@ -101,17 +108,87 @@ impl UbiHandler {
         answers
     }
 
-    fn collect_residual_fen_args(residual_args: &mut SplitWhitespace) -> String {
-        let mut fen_string = String::new();
+    pub fn set(&mut self, mut args: SplitWhitespace) -> Vec<String> {
+        let (Some(square_name), Some(piece_name)) = (args.next(), args.next()) else {
+            return vec!["Usage: set <square> <piece-char>".to_string()];
+        };
+        let Some(coordinate) = Coordinate::from_algebraic(square_name) else {
+            return vec![format!("Invalid square {square_name}.")];
+        };
+        let mut piece_chars = piece_name.chars();
+        let (Some(content), None) = (piece_chars.next().and_then(SquareContent::from_fen_char), piece_chars.next()) else {
+            return vec![format!("Invalid piece {piece_name}.")];
+        };
+
+        let mut game = self.game;
+        if let Err(error) = game.place(&coordinate, content) {
+            return vec![Self::placement_error_message(error, square_name)];
+        }
+        if let Err(message) = game.check_invariants() {
+            return vec![message];
+        }
+
+        self.game = game;
+        vec![]
+    }
+
+    pub fn clear(&mut self, mut args: SplitWhitespace) -> Vec<String> {
+        let Some(square_name) = args.next() else {
+            return vec!["Usage: clear <square>".to_string()];
+        };
+        let Some(coordinate) = Coordinate::from_algebraic(square_name) else {
+            return vec![format!("Invalid square {square_name}.")];
+        };
+
+        match self.game.clear(&coordinate) {
+            Ok(()) => vec![],
+            Err(error) => vec![Self::placement_error_message(error, square_name)],
+        }
+    }
+
+    fn placement_error_message(error: PlacementError, square_name: &str) -> String {
+        match error {
+            PlacementError::OutOfBounds => format!("Square {square_name} is outside of the board."),
+            PlacementError::Occupied => format!("Square {square_name} is already occupied."),
+        }
+    }
+
+    /// There is no search that could be stopped, so there is neither a best move to report nor anything else to say.
+    pub const fn stop() -> Vec<String> {
+        vec![]
+    }
+
+    pub fn display(&self) -> Vec<String> {
+        vec![self.game.to_string()]
+    }
 
-        for arg in residual_args {
+    /// Plays the moves given in move notation on `game`. If one of them is not a valid move, `game` is left untouched.
+    fn apply_move_tokens(game: &mut Game, move_tokens: &[String]) -> Result<(), String> {
+        let mut scratch = *game;
+        for token in move_tokens {
+            let Some(tile_move) = scratch.parse_move(token) else {
+                return Err(format!("Illegal move '{token}'."));
+            };
+            scratch.make_move(&tile_move);
+        }
+
+        *game = scratch;
+        Ok(())
+    }
+
+    /// Splits the arguments following `position fen` into the FEN string, with its fields separated by single spaces,
+    /// and the move tokens following an optional `moves` keyword.
+    fn collect_residual_fen_args(residual_args: &mut SplitWhitespace) -> (String, Vec<String>) {
+        let mut fen_fields = vec![];
+
+        for arg in residual_args.by_ref() {
             if arg == "moves" {
                 break;
             }
-            fen_string.push_str(arg);
+            fen_fields.push(arg);
         }
 
-        fen_string
+        (fen_fields.join(" "), residual_args.map(String::from).collect())
     }
 }
 
@@ -129,9 +206,16 @@ where
     let mut input_buffer = String::new();
 
     loop {
-        input_buffer.clear();
-        input.read_line(&mut input_buffer)?;
-        let input = input_buffer.trim_end();
+        // A read can end in the middle of a line if the input arrives in fragments. Keep collecting until the line is
+        // complete, as its start alone could be a different command. At the end of the input, whatever is left is the
+        // last command.
+        let end_of_input = input.read_line(&mut input_buffer)? == 0;
+        if !end_of_input && !input_buffer.ends_with('\n') {
+            continue;
+        }
+
+        let line = std::mem::take(&mut input_buffer);
+        let input = line.trim_end();
 
         let mut args = input.split_whitespace();
         let cmd_maybe = args.next();
@@ -141,31 +225,43 @@ where
                 "ubi" => handler.ubi(),
                 "isready" => handler.isready(),
                 "position" => handler.position(args),
+                "set" => handler.set(args),
+                "clear" => handler.clear(args),
+                "d" => handler.display(),
+                "stop" => UbiHandler::stop(),
                 "exit" => std::process::exit(0),
                 _ => vec![String::from("Unknown command")],
             };
 
             for response in answer {
-                writeln!(output, "{response}").unwrap();
+                writeln!(output, "{response}")?;
             }
         }
+
+        if end_of_input {
+            return Ok(());
+        }
     }
 }
 
+#[cfg_attr(not(test), allow(dead_code))]
 struct SyncWriter {
     inner: Sender<u8>,
 }
 
+#[cfg_attr(not(test), allow(dead_code))]
 struct SyncReader {
     inner: Receiver<u8>,
 }
 
+#[cfg_attr(not(test), allow(dead_code))]
 impl SyncReader {
     pub const fn new(recv: Receiver<u8>) -> Self {
         Self { inner: recv }
     }
 }
 
+#[cfg_attr(not(test), allow(dead_code))]
 impl SyncWriter {
     pub const fn new(send: Sender<u8>) -> Self {
         Self { inner: send }
@@ -187,9 +283,19 @@ impl Write for SyncWriter {
 }
 
 impl Read for SyncReader {
+    /// Waits for the first byte, so that an empty read only ever means the sending side has hung up, and then takes
+    /// whatever else arrives in quick succession.
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let mut num_bytes = 0;
-        loop {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let Ok(first_byte) = self.inner.recv() else {
+            return Ok(0);
+        };
+        buf[0] = first_byte;
+
+        let mut num_bytes = 1;
+        while num_bytes < buf.len() {
             let r = self.inner.recv_timeout(Duration::from_millis(20));
 
             match r {
@@ -234,16 +340,17 @@ impl Read for SyncReader {
 #[cfg(test)]
 mod tests {
     use std::{
-        io::{BufRead, BufReader, Write},
+        io::{BufRead, BufReader, Read, Write},
         sync::mpsc,
         thread,
         time::Duration,
     };
 
-    use crate::ubi::{SyncReader, SyncWriter};
+    use crate::navigation::Coordinate;
+    use crate::ubi::{SyncReader, SyncWriter, UbiHandler};
+    use crate::{Game, Player};
 
     use super::run_loop;
-    use std::fmt::Write as FmtWrite;
 
     fn connect_to_ubi_loop() -> (
         SyncWriter,
@@ -263,20 +370,132 @@ mod tests {
         (input_send, output_recv, ubi_thread)
     }
 
+    #[test]
+    pub fn edit_position() {
+        let mut handler = UbiHandler::new();
+
+        assert!(handler.position("empty".split_whitespace()).is_empty());
+        assert!(handler.set("c4 Z".split_whitespace()).is_empty());
+        assert!(handler.set("d4 x".split_whitespace()).is_empty());
+        assert!(handler.clear("d4".split_whitespace()).is_empty());
+        let board = handler.display().join("\n");
+
+        assert!(board.lines().any(|line| line == "4 |   |   | Z |   |   |   |   |"));
+        assert_eq!(handler.game.as_fen(), "7/7/7/7/7/2Z4/7/7/7 w");
+    }
+
+    #[test]
+    pub fn edit_position_rejects_invalid_input() {
+        let mut handler = UbiHandler::new();
+
+        assert_eq!(handler.set("k4 Z".split_whitespace()), vec!["Invalid square k4."]);
+        assert_eq!(handler.set("c4 Q".split_whitespace()), vec!["Invalid piece Q."]);
+        assert_eq!(handler.set("c4 ZZ".split_whitespace()), vec!["Invalid piece ZZ."]);
+        assert_eq!(handler.clear("c".split_whitespace()), vec!["Invalid square c."]);
+        assert_eq!(handler.game, Game::empty());
+
+        assert!(handler.set("c4 Z".split_whitespace()).is_empty());
+        assert_eq!(handler.set("c4 x".split_whitespace()), vec!["Square c4 is already occupied."]);
+    }
+
+    #[test]
+    pub fn position_applies_moves() {
+        let mut expected = Game::new();
+        let first_move = expected.valid_moves()[0];
+        expected.make_move(&first_move);
+        let second_move = expected.valid_moves()[0];
+        expected.make_move(&second_move);
+        let mut handler = UbiHandler::new();
+
+        let command = format!("startpos moves {first_move} {second_move}");
+        assert!(handler.position(command.split_whitespace()).is_empty());
+        assert_eq!(handler.game, expected);
+
+        let command = format!("fen {} moves {first_move}", Game::new().as_fen());
+        assert!(handler.position(command.split_whitespace()).is_empty());
+        assert_eq!(handler.game.current_player(), Player::Brown);
+    }
+
+    #[test]
+    pub fn position_rejects_illegal_moves() {
+        let first_move = Game::new().valid_moves()[0];
+        let mut handler = UbiHandler::new();
+
+        let command = format!("startpos moves {first_move} {first_move}");
+        assert_eq!(
+            handler.position(command.split_whitespace()),
+            vec![format!("Illegal move '{first_move}'.")]
+        );
+        assert_eq!(handler.game, Game::empty());
+    }
+
+    #[test]
+    pub fn positions_beyond_the_supply_are_rejected() {
+        let mut handler = UbiHandler::new();
+
+        assert_eq!(
+            handler.position("fen 7/7/7/7/7/7/7/7/ZZZ4 w moves Za1a2".split_whitespace()),
+            vec!["Board holds 3 'Z' tiles, but a player only owns 2."]
+        );
+        assert_eq!(handler.game, Game::empty());
+
+        assert!(handler.set("a1 Z".split_whitespace()).is_empty());
+        assert!(handler.set("b1 Z".split_whitespace()).is_empty());
+        assert_eq!(
+            handler.set("c1 Z".split_whitespace()),
+            vec!["Board holds 3 'Z' tiles, but a player only owns 2."]
+        );
+        assert!(handler.game.is_empty(&Coordinate::new(0, 2)));
+    }
+
+    #[test]
+    pub fn fen_args_without_moves() {
+        let (fen, moves) = UbiHandler::collect_residual_fen_args(&mut "1vd1dv1/2zdz2/7 w".split_whitespace());
+
+        assert_eq!(fen, "1vd1dv1/2zdz2/7 w");
+        assert!(moves.is_empty());
+    }
+
+    #[test]
+    pub fn fen_args_with_moves() {
+        let (fen, moves) = UbiHandler::collect_residual_fen_args(&mut "1vd1dv1/2zdz2/7 w moves Zc2c4 zc8c6".split_whitespace());
+
+        assert_eq!(fen, "1vd1dv1/2zdz2/7 w");
+        assert_eq!(moves, vec!["Zc2c4", "zc8c6"]);
+    }
+
+    #[test]
+    pub fn sync_reader_delivers_in_chunks() {
+        let (tx, rx) = mpsc::channel();
+        let mut writer = SyncWriter::new(tx);
+        let mut reader = SyncReader::new(rx);
+        writer.write_all(b"0123456789").expect("Could not write to channel.");
+
+        let mut buf = [0; 4];
+        assert_eq!(reader.read(&mut buf).expect("Could not read from channel."), 4);
+        assert_eq!(&buf, b"0123");
+        assert_eq!(reader.read(&mut buf).expect("Could not read from channel."), 4);
+        assert_eq!(&buf, b"4567");
+        assert_eq!(reader.read(&mut buf).expect("Could not read from channel."), 2);
+        assert_eq!(&buf[..2], b"89");
+        drop(writer);
+        assert_eq!(reader.read(&mut buf).expect("Could not read from channel."), 0);
+    }
+
     #[test]
     pub fn detect_ubi() {
         let (mut input_send, mut output_recv, _) = connect_to_ubi_loop();
 
         thread::sleep(Duration::from_millis(100));
-        writeln!(input_send, "ubi").unwrap();
+        writeln!(input_send, "ubi").expect("Could not write to UBI input.");
         thread::sleep(Duration::from_millis(100));
 
         // discard first line ...
         let mut buf = String::new();
-        output_recv.read_line(&mut buf).unwrap();
+        output_recv.read_line(&mut buf).expect("Could not read UBI output.");
         print!("{buf}");
         buf.clear();
-        output_recv.read_line(&mut buf).unwrap();
+        output_recv.read_line(&mut buf).expect("Could not read UBI output.");
         print!("{buf}");
         assert_eq!(buf, "ubiok\n");
 
@@ -284,4 +503,68 @@ mod tests {
 
         // println!("{:?}", output);
     }
+
+    #[test]
+    pub fn stop_without_search_is_silent() {
+        let (mut input_send, mut output_recv, _) = connect_to_ubi_loop();
+
+        writeln!(input_send, "ubi\nisready\nstop\nisready").expect("Could not write to UBI input.");
+        thread::sleep(Duration::from_millis(100));
+
+        let mut lines = vec![];
+        for _ in 0..4 {
+            let mut buf = String::new();
+            output_recv.read_line(&mut buf).expect("Could not read UBI output.");
+            lines.push(buf);
+        }
+
+        assert!(lines[0].starts_with("id name"));
+        assert_eq!(lines[1..], ["ubiok\n", "readyok\n", "readyok\n"]);
+    }
+
+    #[test]
+    pub fn fragmented_command_is_read_as_one_line() {
+        let (mut input_send, mut output_recv, _) = connect_to_ubi_loop();
+
+        for byte in b"isready\nubi\n" {
+            input_send.write_all(&[*byte]).expect("Could not write to UBI input.");
+            thread::sleep(Duration::from_millis(30));
+        }
+        thread::sleep(Duration::from_millis(100));
+
+        let mut buf = String::new();
+        output_recv.read_line(&mut buf).expect("Could not read UBI output.");
+        assert!(buf.starts_with("id name"), "unexpected answer {buf:?}");
+        buf.clear();
+        output_recv.read_line(&mut buf).expect("Could not read UBI output.");
+        assert_eq!(buf, "ubiok\n");
+    }
+
+    #[test]
+    pub fn last_command_without_newline_is_run_at_end_of_input() {
+        let (mut input_send, mut output_recv, ubi_thread) = connect_to_ubi_loop();
+
+        write!(input_send, "ubi").expect("Could not write to UBI input.");
+        drop(input_send);
+
+        let mut buf = String::new();
+        output_recv.read_line(&mut buf).expect("Could not read UBI output.");
+        assert!(buf.starts_with("id name"), "unexpected answer {buf:?}");
+        buf.clear();
+        output_recv.read_line(&mut buf).expect("Could not read UBI output.");
+        assert_eq!(buf, "ubiok\n");
+        assert!(ubi_thread.join().expect("UBI loop panicked.").is_ok());
+    }
+
+    #[test]
+    pub fn blank_lines_produce_no_output() {
+        let (mut input_send, mut output_recv, _) = connect_to_ubi_loop();
+
+        write!(input_send, "\n \t  \nubi\n").expect("Could not write to UBI input.");
+        thread::sleep(Duration::from_millis(100));
+
+        let mut buf = String::new();
+        output_recv.read_line(&mut buf).expect("Could not read UBI output.");
+        assert!(buf.starts_with("id name"), "unexpected answer {buf:?}");
+    }
 }